@@ -1,92 +1,95 @@
-// Gemini AI Prompt Templates for MultiversX Rust Contract Generation
-
-export const SYSTEM_PROMPT = `You are an expert MultiversX Rust smart contract developer.
-Generate production-ready code using multiversx-sc framework v0.64.0.
-
-CRITICAL REQUIREMENTS:
-1. Use proper Rust syntax and MultiversX conventions
-2. Include comprehensive error handling with require! macros
-3. Add storage mappers for all state variables
-4. Implement events for important actions
-5. Use #[only_owner] for admin functions
-6. Add detailed inline comments
-7. Follow security best practices (no reentrancy, overflow protection)
-8. Use modern #[type_abi] attribute (NOT TypeAbi derive)
-9. Use multiversx_sc::imports!() and multiversx_sc::derive_imports!()
-10. Events can only have 1 non-indexed data argument (make others indexed)
-
-CODE STRUCTURE:
-- Start with #![no_std]
-- Use multiversx_sc::imports!() and multiversx_sc::derive_imports!()
-- Define contract trait with #[multiversx_sc::contract]
-- Include init and upgrade functions
-- Add all endpoints with proper attributes
-- Define storage mappers
-- Add events
-- Define structs/enums at the end with #[type_abi]
-
-COMMON PITFALLS TO AVOID:
-- DO NOT use TypeAbi derive (deprecated) - use #[type_abi] attribute instead
-- DO NOT use &self in storage mapper definitions
-- DO NOT forget #[storage_mapper("name")] attribute
-- DO NOT use ManagedVec without proper type annotations
-- DO NOT forget to import types (BigUint, TokenIdentifier, etc.)
-- Events MUST have exactly 1 non-indexed data field (use #[indexed] for others)
-- Always use ManagedBuffer for strings, not String or &str
-- Use Self::Api for generic type parameters in structs
-- All endpoints must have proper visibility (#[endpoint], #[view], etc.)
-- Storage mappers must return SingleValueMapper, VecMapper, MapMapper, etc.
-
-MINIMAL EXAMPLE STRUCTURE:
-\`\`\`rust
-#![no_std]
-
-multiversx_sc::imports!();
-multiversx_sc::derive_imports!();
-
-#[multiversx_sc::contract]
-pub trait YourContract {
-    #[init]
-    fn init(&self) {
-        // initialization
-    }
-
-    #[upgrade]
-    fn upgrade(&self) {}
-
-    // endpoints here
-
-    // storage mappers
-    #[view(getCounter)]
-    #[storage_mapper("counter")]
-    fn counter(&self) -> SingleValueMapper<u64>;
-}
-\`\`\`
-
-OUTPUT: Only the complete Rust code for contract.rs, no explanations or markdown.`;
-
-
-export function generateContractPrompt(description: string, category: string): string {
-  return `${SYSTEM_PROMPT}
-
-Create a MultiversX smart contract with the following requirements:
-
-**Description**: ${description}
-
-**Category**: ${category}
-
-**Additional Guidelines**:
-- Keep it simple and focused on core functionality
-- Ensure all functions have clear error messages
-- Add view functions for all important state
-- Include proper access control where needed
-
-Generate the complete contract.rs file now:`;
-}
-
-export const EXAMPLE_CONTRACTS = {
-  token: `Example: Simple fungible token with minting and burning`,
-  nft: `Example: NFT collection with minting and marketplace`,
-  staking: `Example: Staking contract with rewards distribution`,
-  dao: `Example: DAO with proposal voting system`
-};
+// Gemini AI Prompt Templates for MultiversX Rust Contract Generation
+
+// Bump whenever the prompts change, so ratings are tracked per prompt version
+export const PROMPT_VERSION = 'v1';
+
+export const SYSTEM_PROMPT = `You are an expert MultiversX Rust smart contract developer.
+Generate production-ready code using multiversx-sc framework v0.64.0.
+
+CRITICAL REQUIREMENTS:
+1. Use proper Rust syntax and MultiversX conventions
+2. Include comprehensive error handling with require! macros
+3. Add storage mappers for all state variables
+4. Implement events for important actions
+5. Use #[only_owner] for admin functions
+6. Add detailed inline comments
+7. Follow security best practices (no reentrancy, overflow protection)
+8. Use modern #[type_abi] attribute (NOT TypeAbi derive)
+9. Use multiversx_sc::imports!() and multiversx_sc::derive_imports!()
+10. Events can only have 1 non-indexed data argument (make others indexed)
+
+CODE STRUCTURE:
+- Start with #![no_std]
+- Use multiversx_sc::imports!() and multiversx_sc::derive_imports!()
+- Define contract trait with #[multiversx_sc::contract]
+- Include init and upgrade functions
+- Add all endpoints with proper attributes
+- Define storage mappers
+- Add events
+- Define structs/enums at the end with #[type_abi]
+
+COMMON PITFALLS TO AVOID:
+- DO NOT use TypeAbi derive (deprecated) - use #[type_abi] attribute instead
+- DO NOT use &self in storage mapper definitions
+- DO NOT forget #[storage_mapper("name")] attribute
+- DO NOT use ManagedVec without proper type annotations
+- DO NOT forget to import types (BigUint, TokenIdentifier, etc.)
+- Events MUST have exactly 1 non-indexed data field (use #[indexed] for others)
+- Always use ManagedBuffer for strings, not String or &str
+- Use Self::Api for generic type parameters in structs
+- All endpoints must have proper visibility (#[endpoint], #[view], etc.)
+- Storage mappers must return SingleValueMapper, VecMapper, MapMapper, etc.
+
+MINIMAL EXAMPLE STRUCTURE:
+\`\`\`rust
+#![no_std]
+
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+#[multiversx_sc::contract]
+pub trait YourContract {
+    #[init]
+    fn init(&self) {
+        // initialization
+    }
+
+    #[upgrade]
+    fn upgrade(&self) {}
+
+    // endpoints here
+
+    // storage mappers
+    #[view(getCounter)]
+    #[storage_mapper("counter")]
+    fn counter(&self) -> SingleValueMapper<u64>;
+}
+\`\`\`
+
+OUTPUT: Only the complete Rust code for contract.rs, no explanations or markdown.`;
+
+
+export function generateContractPrompt(description: string, category: string): string {
+  return `${SYSTEM_PROMPT}
+
+Create a MultiversX smart contract with the following requirements:
+
+**Description**: ${description}
+
+**Category**: ${category}
+
+**Additional Guidelines**:
+- Keep it simple and focused on core functionality
+- Ensure all functions have clear error messages
+- Add view functions for all important state
+- Include proper access control where needed
+
+Generate the complete contract.rs file now:`;
+}
+
+export const EXAMPLE_CONTRACTS = {
+  token: `Example: Simple fungible token with minting and burning`,
+  nft: `Example: NFT collection with minting and marketplace`,
+  staking: `Example: Staking contract with rewards distribution`,
+  dao: `Example: DAO with proposal voting system`
+};
@@ -3,39 +3,263 @@
 multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
 
+/// Upper bound for the platform fee, in basis points
+const MAX_PLATFORM_FEE_BPS: u64 = 1000; // 10%
+
+/// Upper bounds for the daily generation limit and the minting fee
+const MAX_DAILY_LIMIT: u64 = 100;
+const MAX_MINTING_FEE: u64 = 10_000_000_000_000_000_000; // 10 EGLD
+
+/// Upper bound for the anti-spam deposit on generation requests
+const MAX_GENERATION_DEPOSIT: u64 = 1_000_000_000_000_000_000; // 1 EGLD
+
+/// Version of the storage layout written by this code. `migrateStorage`
+/// migrates older layouts one version at a time.
+const STORAGE_VERSION: u32 = 1;
+
+/// Fee recipient shares must add up to this, in basis points
+const TOTAL_SHARE_BPS: u64 = 10000;
+
+const SECONDS_PER_DAY: u64 = 86400;
+
+/// Delay between proposing and executing a parameter change on new deployments
+const DEFAULT_TIMELOCK_DELAY: u64 = 2 * SECONDS_PER_DAY;
+
+/// Upper bound for the timelock delay, so proposals always become executable
+const MAX_TIMELOCK_DELAY: u64 = 30 * SECONDS_PER_DAY;
+
+/// Time unstaked funds stay locked before they can be withdrawn, on new deployments
+const DEFAULT_UNBONDING_PERIOD: u64 = 7 * SECONDS_PER_DAY;
+
+/// Upper bound for the unbonding period, so unstaked funds are never stuck
+const MAX_UNBONDING_PERIOD: u64 = 30 * SECONDS_PER_DAY;
+
+/// Bids placed this close to an auction's end push the end time back by the same amount
+const AUCTION_EXTENSION_SECONDS: u64 = 600; // 10 minutes
+
+/// Number of phantom votes at the platform-wide mean that every template
+/// score starts from, so a handful of votes can't top the rankings
+const RATING_PRIOR_WEIGHT: u64 = 10;
+
+/// Prior mean used before any template has been rated, scaled by 100
+const DEFAULT_PRIOR_MEAN: u64 = 300;
+
+/// Templates kept on each category leaderboard, and creators on the top creators list
+const LEADERBOARD_SIZE: usize = 10;
+
+/// Reputation points on top of the creator's weighted rating
+const REPUTATION_PER_MINT: u64 = 10;
+const REPUTATION_PER_SALE: u64 = 25;
+const REPUTATION_PER_ACHIEVEMENT: u64 = 50;
+
 /// Natural Language to Rust Smart Contract Generator
 /// Hackathon MVP - Free generation with rate limiting, NFT marketplace, and ratings
 #[multiversx_sc::contract]
 pub trait ContractGenerator {
     // ========== INITIALIZATION ==========
 
+    /// E.g. `3, 50000000000000000, 250` for 3 generations a day, a 0.05 EGLD
+    /// minting fee and a 2.5% platform fee
     #[init]
-    fn init(&self) {
-        self.daily_generation_limit().set(3u64);
-        self.nft_minting_fee()
-            .set(BigUint::from(50000000000000000u64)); // 0.05 EGLD
-        self.platform_fee_percent().set(250u64); // 2.5% (basis points)
+    fn init(&self, daily_limit: u64, minting_fee: BigUint, platform_fee_bps: u64) {
+        self.validate_parameter_change(&ParameterChange::DailyLimit(daily_limit));
+        self.validate_parameter_change(&ParameterChange::MintingFee(minting_fee.clone()));
+        self.validate_parameter_change(&ParameterChange::PlatformFee(platform_fee_bps));
+
+        self.daily_generation_limit().set(daily_limit);
+        self.nft_minting_fee().set(minting_fee);
+        self.platform_fee_percent().set(platform_fee_bps);
+        self.timelock_delay().set(DEFAULT_TIMELOCK_DELAY);
+        self.unbonding_period().set(DEFAULT_UNBONDING_PERIOD);
+        self.storage_version().set(STORAGE_VERSION);
     }
 
-    #[only_owner]
     #[endpoint(setTemplateNftTokenId)]
     fn set_template_nft_token_id(&self, token_id: TokenIdentifier) {
+        self.require_role(Role::Admin);
         self.template_nft_token_id().set(token_id);
     }
 
+    /// Only checks the stored layout. When it is older, every subsystem is
+    /// paused until `migrateStorage` has run to completion.
     #[upgrade]
-    fn upgrade(&self) {}
+    fn upgrade(&self) {
+        let from_version = self.storage_version().get();
+        require!(
+            from_version <= STORAGE_VERSION,
+            "Storage is newer than this code"
+        );
+
+        if from_version < STORAGE_VERSION {
+            for subsystem in [
+                Subsystem::Generation,
+                Subsystem::Minting,
+                Subsystem::Marketplace,
+                Subsystem::Ratings,
+            ] {
+                self.paused(subsystem).set(true);
+                self.pause_changed_event(subsystem, true);
+            }
+        }
+    }
+
+    // ========== MIGRATIONS ==========
+
+    /// Migrates up to `batch_size` records and returns whether the storage is
+    /// now on the current layout. Call it repeatedly after an upgrade, then
+    /// unpause the subsystems.
+    #[endpoint(migrateStorage)]
+    fn migrate_storage(&self, batch_size: u64) -> bool {
+        self.require_role(Role::Admin);
+        require!(batch_size > 0, "Batch size must be positive");
+        let version = self.storage_version().get();
+        require!(version < STORAGE_VERSION, "Storage is up to date");
+
+        if !self.migrate_from(version, batch_size) {
+            return false;
+        }
+
+        self.migration_step().clear();
+        self.migration_cursor().clear();
+        self.storage_version().set(version + 1);
+        self.storage_migrated_event(version, version + 1);
+
+        version + 1 == STORAGE_VERSION
+    }
+
+    /// Migrates the storage layout from `version` towards `version + 1`,
+    /// returning whether that version is complete
+    fn migrate_from(&self, version: u32, batch_size: u64) -> bool {
+        match version {
+            0 => self.migrate_v0_to_v1(batch_size),
+            _ => sc_panic!("Unknown storage version"),
+        }
+    }
+
+    /// Version 0 is the original hackathon deployment. Backfills the
+    /// nonce -> generation index and the aggregates that later versions keep
+    /// up to date as endpoints run, then re-encodes its listings, which are
+    /// indexed by category, and finally builds the rankings, which need the
+    /// platform-wide prior.
+    /// Progress is kept in `migration_step` and `migration_cursor`.
+    fn migrate_v0_to_v1(&self, batch_size: u64) -> bool {
+        if self.timelock_delay().is_empty() {
+            self.timelock_delay().set(DEFAULT_TIMELOCK_DELAY);
+        }
+        if self.unbonding_period().is_empty() {
+            self.unbonding_period().set(DEFAULT_UNBONDING_PERIOD);
+        }
+
+        let mut step = self.migration_step().get();
+        let mut cursor = self.migration_cursor().get();
+        let mut remaining = batch_size;
+        while step < 3 {
+            let end = if step == 1 {
+                self.next_listing_id().get()
+            } else {
+                self.next_generation_id().get()
+            };
+            if cursor >= end {
+                step += 1;
+                cursor = 0;
+                continue;
+            }
+            if remaining == 0 {
+                break;
+            }
+
+            match step {
+                0 => self.backfill_v0_generation(cursor),
+                1 => self.migrate_v0_listing(cursor),
+                _ => self.rank_v0_generation(cursor),
+            }
+            cursor += 1;
+            remaining -= 1;
+        }
+
+        self.migration_step().set(step);
+        self.migration_cursor().set(cursor);
+        step == 3
+    }
+
+    fn backfill_v0_generation(&self, generation_id: u64) {
+        let generation = self.generations(generation_id).get();
+        let nft_nonce = generation.nft_nonce;
+        if nft_nonce == 0 {
+            return;
+        }
+
+        self.template_generation(nft_nonce).set(generation_id);
+        self.creator_minted_count(&generation.creator)
+            .update(|count| *count += 1);
+
+        let rating = self.template_ratings(nft_nonce).get();
+        if rating.rating_count > 0 {
+            self.global_rating_total()
+                .update(|total| *total += rating.total_rating);
+            self.global_rating_count()
+                .update(|count| *count += rating.rating_count);
+            self.creator_ratings(&generation.creator).update(|attrs| {
+                attrs.total_rating += rating.total_rating;
+                attrs.rating_count += rating.rating_count;
+            });
+        }
+    }
+
+    /// Version 0 listings were EGLD-only and stayed in storage once sold
+    fn migrate_v0_listing(&self, listing_id: u64) {
+        let old_listing_mapper = self.listings_v0(listing_id);
+        if old_listing_mapper.is_empty() {
+            return;
+        }
+
+        let old_listing = old_listing_mapper.take();
+        if !old_listing.active {
+            return;
+        }
+
+        let listing = Listing {
+            id: old_listing.id,
+            seller: old_listing.seller,
+            nft_nonce: old_listing.nft_nonce,
+            payment_token: EgldOrEsdtTokenIdentifier::egld(),
+            price: old_listing.price,
+            expires_at: 0,
+        };
+        self.listings(listing_id).set(&listing);
+        self.index_listing(&listing);
+    }
+
+    fn rank_v0_generation(&self, generation_id: u64) {
+        let generation = self.generations(generation_id).get();
+        if generation.nft_nonce != 0 {
+            self.refresh_leaderboard(generation.nft_nonce);
+            self.refresh_creator_ranking(&generation.creator);
+        }
+    }
 
     // ========== GENERATION SYSTEM (FREE with Rate Limiting) ==========
 
+    /// Requires the configured anti-spam deposit in EGLD, if any. It is
+    /// refunded when the oracle completes the generation, or can be reclaimed
+    /// while generation is paused.
+    #[payable("EGLD")]
     #[endpoint(generateContract)]
     fn generate_contract(&self, description: ManagedBuffer, category: ManagedBuffer) -> u64 {
+        self.require_not_paused(Subsystem::Generation);
+        self.require_caller_allowed();
         let caller = self.blockchain().get_caller();
 
+        let deposit = self.call_value().egld().clone();
+        require!(
+            deposit == self.generation_deposit_amount().get(),
+            "Wrong generation deposit"
+        );
+
         // Check rate limit
         require!(
             self.check_and_update_rate_limit(&caller),
-            "Daily generation limit reached (3/day)"
+            "Daily generation limit reached"
         );
 
         // Create generation request
@@ -46,7 +270,7 @@ pub trait ContractGenerator {
             creator: caller.clone(),
             description: description.clone(),
             category: category.clone(),
-            timestamp: self.blockchain().get_block_timestamp(),
+            timestamp: self.current_timestamp(),
             status: GenerationStatus::Pending,
             code_hash: ManagedBuffer::new(),
             nft_nonce: 0,
@@ -54,6 +278,9 @@ pub trait ContractGenerator {
 
         self.generations(generation_id).set(&generation);
         self.next_generation_id().set(generation_id + 1);
+        if deposit > 0u64 {
+            self.generation_deposits(generation_id).set(deposit);
+        }
 
         // Track user's generations
         self.user_generation_count(&caller)
@@ -70,14 +297,28 @@ pub trait ContractGenerator {
         generation_id
     }
 
-    /// Oracle callback - only contract owner (backend service) can call
-    #[only_owner]
+    /// Oracle callback - only the backend service (oracle role) can call
+    /// `abusive` holds the deposit back for a moderator to slash instead of refunding it
+    /// `generator_version` identifies the model/prompt version that produced the code
     #[endpoint(completeGeneration)]
-    fn complete_generation(&self, generation_id: u64, code_hash: ManagedBuffer, success: bool) {
+    fn complete_generation(
+        &self,
+        generation_id: u64,
+        code_hash: ManagedBuffer,
+        success: bool,
+        abusive: bool,
+        generator_version: OptionalValue<ManagedBuffer>,
+    ) {
+        self.require_role(Role::Oracle);
+        self.require_not_paused(Subsystem::Generation);
         require!(
             !self.generations(generation_id).is_empty(),
             "Generation not found"
         );
+        require!(
+            self.generations(generation_id).get().status == GenerationStatus::Pending,
+            "Generation already completed"
+        );
 
         self.generations(generation_id).update(|generation| {
             generation.status = if success {
@@ -88,15 +329,64 @@ pub trait ContractGenerator {
             generation.code_hash = code_hash.clone();
         });
 
+        if let Some(version) = generator_version.into_option() {
+            self.generator_versions().insert(version.clone());
+            self.generation_generator_version(generation_id)
+                .set(version);
+        }
+
         let generation = self.generations(generation_id).get();
 
+        // Failed generations are refunded too, abusive ones wait for a moderator
+        let deposit = self.generation_deposits(generation_id).get();
+        if abusive {
+            self.generation_deposit_held_event(generation_id, generation.creator.clone(), deposit);
+        } else if deposit > 0u64 {
+            self.generation_deposits(generation_id).clear();
+            self.send().direct_egld(&generation.creator, &deposit);
+            self.generation_deposit_refunded_event(
+                generation_id,
+                generation.creator.clone(),
+                deposit,
+            );
+        }
+
         self.generation_completed_event(generation_id, generation.creator, success, code_hash);
     }
 
+    /// Lets the creator take back the deposit of a request the oracle has not
+    /// completed while generation is paused. The request itself stays pending.
+    #[endpoint(reclaimGenerationDeposit)]
+    fn reclaim_generation_deposit(&self, generation_id: u64) {
+        require!(
+            self.paused(Subsystem::Generation).get(),
+            "Generation is not paused"
+        );
+        require!(
+            !self.generations(generation_id).is_empty(),
+            "Generation not found"
+        );
+        let generation = self.generations(generation_id).get();
+        require!(
+            generation.creator == self.blockchain().get_caller(),
+            "Only the creator can reclaim the deposit"
+        );
+        require!(
+            generation.status == GenerationStatus::Pending,
+            "Generation already completed"
+        );
+
+        let deposit = self.generation_deposits(generation_id).take();
+        require!(deposit > 0u64, "No deposit held for generation");
+
+        self.send().direct_egld(&generation.creator, &deposit);
+        self.generation_deposit_refunded_event(generation_id, generation.creator, deposit);
+    }
+
     // ========== RATE LIMITING ==========
 
     fn check_and_update_rate_limit(&self, user: &ManagedAddress) -> bool {
-        let current_day = self.blockchain().get_block_timestamp() / 86400; // Seconds in a day
+        let current_day = self.current_timestamp() / 86400; // Seconds in a day
         let last_day = self.user_last_generation_day(user).get();
 
         // Reset counter if it's a new day
@@ -106,7 +396,7 @@ pub trait ContractGenerator {
         }
 
         let generations_today = self.user_generations_today(user).get();
-        let limit = self.daily_generation_limit().get();
+        let limit = self.effective_daily_limit(user);
 
         if generations_today >= limit {
             return false;
@@ -117,18 +407,122 @@ pub trait ContractGenerator {
         true
     }
 
+    // ========== STAKING ==========
+
+    /// Stakes the stake token (EGLD unless configured otherwise) to unlock
+    /// the daily limit of the highest stake tier reached.
+    #[payable("*")]
+    #[endpoint(stake)]
+    fn stake(&self) {
+        self.require_caller_allowed();
+        let payment = self.call_value().egld_or_single_esdt();
+        require!(
+            payment.token_identifier == self.get_stake_token(),
+            "Wrong stake token"
+        );
+        require!(payment.amount > 0u64, "Amount must be greater than 0");
+
+        let caller = self.blockchain().get_caller();
+        self.user_stake(&caller)
+            .update(|stake| *stake += &payment.amount);
+        self.total_staked()
+            .update(|total| *total += &payment.amount);
+
+        self.staked_event(caller, payment.amount);
+    }
+
+    /// Lowers the stake (and quota) right away. The amount can be withdrawn
+    /// once the unbonding period is over; unstaking again restarts it.
+    #[endpoint(unstake)]
+    fn unstake(&self, amount: BigUint) {
+        let caller = self.blockchain().get_caller();
+        let stake_mapper = self.user_stake(&caller);
+        let stake = stake_mapper.get();
+        require!(amount > 0u64, "Amount must be greater than 0");
+        require!(amount <= stake, "Amount exceeds stake");
+
+        stake_mapper.set(&stake - &amount);
+
+        let unlock_at = self.current_timestamp() + self.unbonding_period().get();
+        self.user_unbonding_amount(&caller)
+            .update(|unbonding| *unbonding += &amount);
+        self.user_unbonding_unlock_at(&caller).set(unlock_at);
+
+        self.unstaked_event(caller, unlock_at, amount);
+    }
+
+    #[endpoint(withdrawUnbonded)]
+    fn withdraw_unbonded(&self) {
+        let caller = self.blockchain().get_caller();
+        let amount = self.user_unbonding_amount(&caller).get();
+        require!(amount > 0u64, "Nothing to withdraw");
+        require!(
+            self.current_timestamp() >= self.user_unbonding_unlock_at(&caller).get(),
+            "Unbonding period not over"
+        );
+
+        self.user_unbonding_amount(&caller).clear();
+        self.user_unbonding_unlock_at(&caller).clear();
+        self.total_staked().update(|total| *total -= &amount);
+        self.send()
+            .direct(&caller, &self.get_stake_token(), 0, &amount);
+
+        self.unbonded_withdrawn_event(caller, amount);
+    }
+
+    /// The stake token can only be changed while nothing is staked
+    #[endpoint(setStakeToken)]
+    fn set_stake_token(&self, token: EgldOrEsdtTokenIdentifier) {
+        self.require_role(Role::Admin);
+        require!(token.is_valid(), "Invalid token identifier");
+        require!(self.total_staked().get() == 0u64, "Stakes are still held");
+        self.stake_token().set(token);
+    }
+
+    fn get_stake_token(&self) -> EgldOrEsdtTokenIdentifier {
+        let stake_token = self.stake_token();
+        if stake_token.is_empty() {
+            EgldOrEsdtTokenIdentifier::egld()
+        } else {
+            stake_token.get()
+        }
+    }
+
+    /// Index of the highest tier reached, starting at 1. 0 means no tier.
+    fn stake_tier_index(&self, user: &ManagedAddress) -> usize {
+        let stake = self.user_stake(user).get();
+        let mut tier_index = 0;
+        for (index, tier) in self.stake_tiers().iter().enumerate() {
+            if stake >= tier.min_stake {
+                tier_index = index + 1;
+            }
+        }
+        tier_index
+    }
+
+    /// Stake tiers never lower the limit below the base daily limit
+    fn effective_daily_limit(&self, user: &ManagedAddress) -> u64 {
+        let base_limit = self.daily_generation_limit().get();
+        match self.stake_tier_index(user) {
+            0 => base_limit,
+            tier_index => {
+                let tier = self.stake_tiers().get(tier_index);
+                base_limit.max(tier.daily_limit)
+            }
+        }
+    }
+
     // ========== NFT MINTING ==========
 
     #[payable("EGLD")]
     #[endpoint(mintTemplateNFT)]
     fn mint_template_nft(&self, generation_id: u64, name: ManagedBuffer) -> u64 {
-        let payment = self.call_value().egld_value();
+        self.require_not_paused(Subsystem::Minting);
+        self.require_caller_allowed();
+        let payment = self.call_value().egld().clone();
         let required_fee = self.nft_minting_fee().get();
 
-        require!(
-            payment.clone_value() >= required_fee,
-            "Insufficient minting fee"
-        );
+        require!(payment >= required_fee, "Insufficient minting fee");
 
         let generation = self.generations(generation_id).get();
         require!(
@@ -171,6 +565,16 @@ pub trait ContractGenerator {
         self.generations(generation_id).update(|generation| {
             generation.nft_nonce = nft_nonce;
         });
+        self.template_generation(nft_nonce).set(generation_id);
+        self.creator_minted_count(&generation.creator)
+            .update(|count| *count += 1);
+
+        // Minting fee is kept by the platform
+        self.accrue_fees(
+            FeeSource::Minting,
+            &EgldOrEsdtTokenIdentifier::egld(),
+            &payment,
+        );
 
         // Send NFT to creator
         let caller = self.blockchain().get_caller();
@@ -179,6 +583,7 @@ pub trait ContractGenerator {
 
         // Check for achievements
         self.check_first_generation_achievement(&caller);
+        self.refresh_creator_ranking(&caller);
 
         self.template_nft_minted_event(generation_id, nft_nonce, caller);
 
@@ -187,19 +592,29 @@ pub trait ContractGenerator {
 
     // ========== MARKETPLACE ==========
 
+    /// Lists a template NFT for sale. The price is denominated in EGLD unless
+    /// `payment_token` names one of the accepted ESDTs. Listings without
+    /// `expires_at` never expire.
     #[payable("*")]
     #[endpoint(listTemplate)]
-    fn list_template(&self, nft_nonce: u64, price: BigUint) -> u64 {
-        let payment = self.call_value().single_esdt();
-        let nft_token_id = self.template_nft_token_id().get();
+    fn list_template(
+        &self,
+        nft_nonce: u64,
+        price: BigUint,
+        payment_token: OptionalValue<EgldOrEsdtTokenIdentifier>,
+        expires_at: OptionalValue<u64>,
+    ) -> u64 {
+        self.require_not_paused(Subsystem::Marketplace);
+        self.require_caller_allowed();
+        self.require_template_nft_payment(nft_nonce);
+        require!(price > BigUint::zero(), "Price must be greater than 0");
 
-        require!(payment.token_identifier == nft_token_id, "Wrong token");
-        require!(payment.token_nonce == nft_nonce, "Wrong NFT nonce");
+        let payment_token = self.resolve_payment_token(payment_token);
+        let expires_at = expires_at.into_option().unwrap_or_default();
         require!(
-            payment.amount == BigUint::from(1u64),
-            "Must send exactly 1 NFT"
+            expires_at == 0 || expires_at > self.current_timestamp(),
+            "Expiry must be in the future"
         );
-        require!(price > BigUint::zero(), "Price must be greater than 0");
 
         let caller = self.blockchain().get_caller();
         let listing_id = self.next_listing_id().get();
@@ -208,211 +623,2497 @@ pub trait ContractGenerator {
             id: listing_id,
             seller: caller.clone(),
             nft_nonce,
+            payment_token: payment_token.clone(),
             price: price.clone(),
-            active: true,
+            expires_at,
         };
 
         self.listings(listing_id).set(&listing);
         self.next_listing_id().set(listing_id + 1);
+        self.index_listing(&listing);
 
         // Store NFT in contract
         // (already received via payable)
 
-        self.template_listed_event(listing_id, nft_nonce, caller, price);
+        self.template_listed_event(listing_id, nft_nonce, caller, payment_token, price);
 
         listing_id
     }
 
-    #[payable("EGLD")]
+    #[payable("*")]
     #[endpoint(purchaseTemplate)]
     fn purchase_template(&self, listing_id: u64) {
-        let payment = self.call_value().egld_value();
+        self.require_not_paused(Subsystem::Marketplace);
+        self.require_caller_allowed();
+        let payment = self.call_value().egld_or_single_esdt();
+        require!(!self.listings(listing_id).is_empty(), "Listing not active");
         let listing = self.listings(listing_id).get();
 
-        require!(listing.active, "Listing not active");
+        require!(!self.is_listing_expired(&listing), "Listing has expired");
+        self.require_template_not_flagged(listing.nft_nonce);
         require!(
-            payment.clone_value() >= listing.price,
-            "Insufficient payment"
+            payment.token_identifier == listing.payment_token,
+            "Wrong payment token"
         );
+        require!(payment.amount >= listing.price, "Insufficient payment");
 
         let buyer = self.blockchain().get_caller();
         require!(buyer != listing.seller, "Cannot buy your own template");
 
-        // Calculate fees
-        let platform_fee_percent = self.platform_fee_percent().get();
-        let platform_fee = &listing.price * platform_fee_percent / 10000u64;
-        let seller_amount = &listing.price - &platform_fee;
-
-        // Send payment to seller
-        self.send().direct_egld(&listing.seller, &seller_amount);
-
-        // Platform fee stays in contract (can be withdrawn by owner)
+        // Return any overpayment to the buyer
+        let excess = &payment.amount - &listing.price;
+        self.send()
+            .direct_non_zero(&buyer, &listing.payment_token, 0, &excess);
 
-        // Transfer NFT to buyer
-        let nft_token_id = self.template_nft_token_id().get();
-        self.send().direct_esdt(
-            &buyer,
-            &nft_token_id,
+        // Pay the seller and creator, platform fee stays in contract (can be withdrawn by owner)
+        let proceeds = self.settle_sale(
+            &listing.seller,
             listing.nft_nonce,
-            &BigUint::from(1u64),
+            &listing.payment_token,
+            &listing.price,
         );
 
-        // Deactivate listing
-        self.listings(listing_id).update(|l| {
-            l.active = false;
-        });
+        // Transfer NFT to buyer
+        self.send_template_nft(&buyer, listing.nft_nonce);
+
+        // Settled listings are removed from storage
+        self.close_listing(listing_id);
 
         // Update template usage stats
-        self.template_uses(listing.nft_nonce)
-            .update(|uses| *uses += 1);
+        self.record_template_sale(&buyer, listing.nft_nonce);
 
         // Check for achievements
         self.check_first_sale_achievement(&listing.seller);
         self.check_popular_template_achievement(listing.nft_nonce);
 
-        self.template_purchased_event(listing_id, buyer, listing.seller, listing.price);
-    }
-
-    #[only_owner]
-    #[endpoint(cancelListing)]
-    fn cancel_listing(&self, listing_id: u64) {
-        let listing = self.listings(listing_id).get();
-        require!(listing.active, "Listing not active");
-
-        // Return NFT to seller
-        let nft_token_id = self.template_nft_token_id().get();
-        self.send().direct_esdt(
-            &listing.seller,
-            &nft_token_id,
-            listing.nft_nonce,
-            &BigUint::from(1u64),
+        self.template_purchased_event(
+            listing_id,
+            buyer,
+            listing.seller,
+            listing.payment_token,
+            proceeds,
         );
-
-        // Deactivate listing
-        self.listings(listing_id).update(|l| {
-            l.active = false;
-        });
     }
 
-    // ========== RATING SYSTEM ==========
-
-    #[endpoint(rateTemplate)]
-    fn rate_template(&self, nft_nonce: u64, rating: u8) {
-        require!(rating >= 1 && rating <= 5, "Rating must be 1-5");
-
-        let caller = self.blockchain().get_caller();
+    /// Splits a sale price into the platform fee, the creator royalty and the
+    /// seller's share, pays the seller and creator and accrues the fee.
+    /// The NFT must be held by the contract when this is called.
+    fn settle_sale(
+        &self,
+        seller: &ManagedAddress,
+        nft_nonce: u64,
+        token: &EgldOrEsdtTokenIdentifier,
+        price: &BigUint,
+    ) -> SaleProceeds<Self::Api> {
+        let platform_fee_percent = self.platform_fee_percent().get();
+        let platform_fee = price * platform_fee_percent / 10000u64;
 
-        // Simple check: prevent duplicate ratings (can be improved)
-        let user_rating_key = self.user_template_rating(&caller, nft_nonce);
-        require!(user_rating_key.is_empty(), "Already rated this template");
+        // Royalties are read from the NFT itself and only paid on resales
+        let token_data = self.held_template_data(nft_nonce);
+        let attributes: TemplateAttributes<Self::Api> = token_data.decode_attributes();
+        let creator = self.generations(attributes.generation_id).get().creator;
+        let royalty = if &creator != seller {
+            price * &token_data.royalties / 10000u64
+        } else {
+            BigUint::zero()
+        };
 
-        // Store user's rating
-        user_rating_key.set(rating);
+        let seller_amount = price - &platform_fee - &royalty;
 
-        // Update template aggregate rating
-        self.template_ratings(nft_nonce).update(|attrs| {
-            attrs.total_rating += rating as u64;
-            attrs.rating_count += 1;
-        });
+        self.pay_out(seller, token, &seller_amount);
+        self.accrue_fees(FeeSource::Marketplace, token, &platform_fee);
 
-        self.template_rated_event(nft_nonce, caller, rating);
-    }
+        // Sales and rentals of the creator's templates count towards their profile
+        self.creator_sales_count(&creator)
+            .update(|count| *count += 1);
+        self.creator_sales_volume(&creator, token)
+            .update(|volume| *volume += price);
+        self.creator_sale_tokens(&creator).insert(token.clone());
 
-    // ========== ACHIEVEMENTS (Event-based, no NFT minting) ==========
+        if royalty > 0u64 {
+            self.pay_out(&creator, token, &royalty);
+            self.creator_royalties_earned(&creator, token)
+                .update(|earned| *earned += &royalty);
+            self.royalty_paid_event(nft_nonce, creator.clone(), token.clone(), royalty.clone());
+        }
+        self.refresh_creator_ranking(&creator);
 
-    fn check_first_generation_achievement(&self, user: &ManagedAddress) {
-        let count = self.user_generation_count(user).get();
-        if count == 1 {
-            self.achievement_earned_event(user.clone(), ManagedBuffer::from(b"First Generation"));
+        SaleProceeds {
+            price: price.clone(),
+            platform_fee,
+            creator,
+            royalty,
+            seller_amount,
         }
     }
 
-    fn check_first_sale_achievement(&self, user: &ManagedAddress) {
-        // Simple implementation: emit event (frontend tracks)
-        self.achievement_earned_event(user.clone(), ManagedBuffer::from(b"First Sale"));
+    /// Counts a sale towards the template's uses and remembers the buyer as
+    /// eligible to rate it.
+    fn record_template_sale(&self, buyer: &ManagedAddress, nft_nonce: u64) {
+        self.template_uses(nft_nonce).update(|uses| *uses += 1);
+        self.template_acquired(buyer, nft_nonce).set(true);
+        self.refresh_leaderboard(nft_nonce);
     }
 
-    fn check_popular_template_achievement(&self, nft_nonce: u64) {
-        let uses = self.template_uses(nft_nonce).get();
-        if uses == 10 {
-            self.achievement_earned_event(
-                ManagedAddress::zero(), // Template-based, not user-based
-                ManagedBuffer::from(b"Popular Template"),
-            );
+    /// Sends sale proceeds directly, or credits them to the recipient's
+    /// claimable balance when pull payments are enabled.
+    fn pay_out(&self, to: &ManagedAddress, token: &EgldOrEsdtTokenIdentifier, amount: &BigUint) {
+        if amount == &BigUint::zero() {
+            return;
         }
-    }
-
-    // ========== ADMIN FUNCTIONS ==========
 
-    #[only_owner]
-    #[endpoint(setDailyLimit)]
-    fn set_daily_limit(&self, new_limit: u64) {
-        self.daily_generation_limit().set(new_limit);
+        if self.pull_payments_enabled().get() {
+            self.credit_proceeds(to, token, amount);
+        } else {
+            self.send().direct(to, token, 0, amount);
+        }
     }
 
-    #[only_owner]
-    #[endpoint(setMintingFee)]
-    fn set_minting_fee(&self, new_fee: BigUint) {
-        self.nft_minting_fee().set(new_fee);
+    /// Adds to the recipient's balance claimable through `claimProceeds`
+    fn credit_proceeds(
+        &self,
+        to: &ManagedAddress,
+        token: &EgldOrEsdtTokenIdentifier,
+        amount: &BigUint,
+    ) {
+        self.pending_proceeds(to, token)
+            .update(|pending| *pending += amount);
+        self.pending_proceeds_tokens(to).insert(token.clone());
     }
 
-    #[only_owner]
-    #[endpoint(withdrawFees)]
-    fn withdraw_fees(&self) {
-        let balance = self
-            .blockchain()
-            .get_sc_balance(&EgldOrEsdtTokenIdentifier::egld(), 0);
-        let owner = self.blockchain().get_owner_address();
-        self.send().direct_egld(&owner, &balance);
-    }
+    /// Claims accumulated proceeds and royalties, for one token or all of them.
+    #[endpoint(claimProceeds)]
+    fn claim_proceeds(&self, token: OptionalValue<EgldOrEsdtTokenIdentifier>) {
+        let caller = self.blockchain().get_caller();
+        let tokens: ManagedVec<EgldOrEsdtTokenIdentifier> = match token.into_option() {
+            Some(token) => ManagedVec::from_single_item(token),
+            None => self.pending_proceeds_tokens(&caller).iter().collect(),
+        };
 
-    // ========== VIEW FUNCTIONS ==========
+        let mut claimed_any = false;
+        for token in tokens.iter() {
+            let amount = self.pending_proceeds(&caller, &token).take();
+            self.pending_proceeds_tokens(&caller).swap_remove(&token);
+            if amount == 0u64 {
+                continue;
+            }
 
-    #[view(getGeneration)]
-    fn get_generation(&self, generation_id: u64) -> Generation<Self::Api> {
-        self.generations(generation_id).get()
-    }
+            claimed_any = true;
+            self.send().direct(&caller, &token, 0, &amount);
+            self.proceeds_claimed_event(caller.clone(), token.clone(), amount);
+        }
 
-    #[view(getListing)]
-    fn get_listing(&self, listing_id: u64) -> Listing<Self::Api> {
-        self.listings(listing_id).get()
+        require!(claimed_any, "Nothing to claim");
     }
 
-    #[view(getUserGenerationsToday)]
-    fn get_user_generations_today(&self, user: ManagedAddress) -> u64 {
-        let current_day = self.blockchain().get_block_timestamp() / 86400;
-        let last_day = self.user_last_generation_day(&user).get();
-
-        if current_day > last_day {
-            0 // New day, counter reset
-        } else {
-            self.user_generations_today(&user).get()
-        }
+    /// Defaults to EGLD and checks that an explicitly chosen ESDT is accepted.
+    fn resolve_payment_token(
+        &self,
+        payment_token: OptionalValue<EgldOrEsdtTokenIdentifier>,
+    ) -> EgldOrEsdtTokenIdentifier {
+        let token = payment_token
+            .into_option()
+            .unwrap_or_else(EgldOrEsdtTokenIdentifier::egld);
+        require!(
+            token.is_egld()
+                || self
+                    .accepted_payment_tokens()
+                    .contains(&token.clone().unwrap_esdt()),
+            "Payment token not accepted"
+        );
+        token
     }
 
-    #[view(getUserGenerationCount)]
-    fn get_user_generation_count(&self, user: ManagedAddress) -> u64 {
-        self.user_generation_count(&user).get()
+    fn require_template_nft_payment(&self, nft_nonce: u64) {
+        let payment = self.call_value().single_esdt();
+        let nft_token_id = self.template_nft_token_id().get();
+
+        require!(payment.token_identifier == nft_token_id, "Wrong token");
+        require!(payment.token_nonce == nft_nonce, "Wrong NFT nonce");
+        require!(payment.amount == 1u64, "Must send exactly 1 NFT");
+        self.require_template_not_flagged(nft_nonce);
     }
 
-    #[view(getTemplateRating)]
-    fn get_template_rating(&self, nft_nonce: u64) -> TemplateRatingInfo {
-        self.template_ratings(nft_nonce).get()
+    /// Token data of a template NFT currently held by the contract
+    fn held_template_data(&self, nft_nonce: u64) -> EsdtTokenData<Self::Api> {
+        let nft_token_id = self.template_nft_token_id().get();
+        self.blockchain().get_esdt_token_data(
+            &self.blockchain().get_sc_address(),
+            &nft_token_id,
+            nft_nonce,
+        )
     }
 
-    #[view(getTemplateUses)]
-    fn get_template_uses(&self, nft_nonce: u64) -> u64 {
-        self.template_uses(nft_nonce).get()
+    fn send_template_nft(&self, to: &ManagedAddress, nft_nonce: u64) {
+        let nft_token_id = self.template_nft_token_id().get();
+        self.send()
+            .direct_esdt(to, &nft_token_id, nft_nonce, &BigUint::from(1u64));
     }
 
-    #[view(getDailyLimit)]
+    #[endpoint(cancelListing)]
+    fn cancel_listing(&self, listing_id: u64) {
+        self.require_role(Role::Moderator);
+        require!(!self.listings(listing_id).is_empty(), "Listing not active");
+        let listing = self.listings(listing_id).get();
+
+        // Return NFT to seller
+        self.send_template_nft(&listing.seller, listing.nft_nonce);
+
+        self.close_listing(listing_id);
+
+        self.listing_cancelled_event(
+            listing_id,
+            listing.nft_nonce,
+            self.blockchain().get_caller(),
+        );
+    }
+
+    /// Sellers can take their template back at any time, even while the
+    /// marketplace is paused.
+    #[endpoint(withdrawListing)]
+    fn withdraw_listing(&self, listing_id: u64) {
+        require!(!self.listings(listing_id).is_empty(), "Listing not active");
+        let listing = self.listings(listing_id).get();
+        require!(
+            listing.seller == self.blockchain().get_caller(),
+            "Only the seller can withdraw"
+        );
+
+        self.send_template_nft(&listing.seller, listing.nft_nonce);
+
+        self.close_listing(listing_id);
+
+        self.listing_withdrawn_event(listing_id, listing.nft_nonce, listing.seller);
+    }
+
+    /// Anyone can return an expired listing's NFT to its seller.
+    #[endpoint(reclaimExpiredListing)]
+    fn reclaim_expired_listing(&self, listing_id: u64) {
+        require!(!self.listings(listing_id).is_empty(), "Listing not active");
+        let listing = self.listings(listing_id).get();
+        require!(self.is_listing_expired(&listing), "Listing has not expired");
+
+        self.send_template_nft(&listing.seller, listing.nft_nonce);
+
+        self.close_listing(listing_id);
+
+        self.listing_expired_event(listing_id, listing.nft_nonce, listing.seller);
+    }
+
+    fn is_listing_expired(&self, listing: &Listing<Self::Api>) -> bool {
+        listing.expires_at != 0 && self.current_timestamp() >= listing.expires_at
+    }
+
+    fn close_listing(&self, listing_id: u64) {
+        let listing = self.listings(listing_id).take();
+        let category = self.template_category(listing.nft_nonce);
+
+        self.active_listing_ids().remove(&listing_id);
+        self.seller_listing_ids(&listing.seller).remove(&listing_id);
+        self.category_listing_ids(&category).remove(&listing_id);
+    }
+
+    fn index_listing(&self, listing: &Listing<Self::Api>) {
+        let category = self.template_category(listing.nft_nonce);
+
+        self.active_listing_ids().insert(listing.id);
+        self.seller_listing_ids(&listing.seller).insert(listing.id);
+        self.category_listing_ids(&category).insert(listing.id);
+    }
+
+    // ========== BUNDLES ==========
+
+    /// Lists several template NFTs, sent in one multi-transfer, to be sold
+    /// together for a single price.
+    #[payable("*")]
+    #[endpoint(listBundle)]
+    fn list_bundle(
+        &self,
+        price: BigUint,
+        payment_token: OptionalValue<EgldOrEsdtTokenIdentifier>,
+    ) -> u64 {
+        self.require_not_paused(Subsystem::Marketplace);
+        self.require_caller_allowed();
+        let payments = self.call_value().all_esdt_transfers().clone();
+        require!(payments.len() >= 2, "Bundle needs at least 2 templates");
+        require!(price > BigUint::zero(), "Price must be greater than 0");
+
+        let nft_token_id = self.template_nft_token_id().get();
+        let mut nft_nonces = ManagedVec::new();
+        for payment in payments.iter() {
+            require!(payment.token_identifier == nft_token_id, "Wrong token");
+            require!(payment.amount == 1u64, "Must send exactly 1 NFT");
+            self.require_template_not_flagged(payment.token_nonce);
+            nft_nonces.push(payment.token_nonce);
+        }
+
+        let payment_token = self.resolve_payment_token(payment_token);
+        let caller = self.blockchain().get_caller();
+        let bundle_id = self.next_bundle_id().get();
+
+        let bundle = BundleListing {
+            id: bundle_id,
+            seller: caller.clone(),
+            nft_nonces,
+            payment_token,
+            price: price.clone(),
+        };
+
+        self.bundles(bundle_id).set(&bundle);
+        self.next_bundle_id().set(bundle_id + 1);
+        self.active_bundle_ids().insert(bundle_id);
+
+        self.bundle_listed_event(bundle_id, caller, price);
+
+        bundle_id
+    }
+
+    #[payable("*")]
+    #[endpoint(purchaseBundle)]
+    fn purchase_bundle(&self, bundle_id: u64) {
+        self.require_not_paused(Subsystem::Marketplace);
+        self.require_caller_allowed();
+        let payment = self.call_value().egld_or_single_esdt();
+        require!(!self.bundles(bundle_id).is_empty(), "Bundle not active");
+        let bundle = self.bundles(bundle_id).get();
+
+        for nft_nonce in bundle.nft_nonces.iter() {
+            self.require_template_not_flagged(nft_nonce);
+        }
+        require!(
+            payment.token_identifier == bundle.payment_token,
+            "Wrong payment token"
+        );
+        require!(payment.amount >= bundle.price, "Insufficient payment");
+
+        let buyer = self.blockchain().get_caller();
+        require!(buyer != bundle.seller, "Cannot buy your own bundle");
+
+        // Return any overpayment to the buyer
+        let excess = &payment.amount - &bundle.price;
+        self.send()
+            .direct_non_zero(&buyer, &bundle.payment_token, 0, &excess);
+
+        // The price is split evenly between the templates so each creator
+        // gets royalties on their part; the last one takes the remainder
+        let template_count = bundle.nft_nonces.len();
+        let share = &bundle.price / template_count as u64;
+        let mut remaining = bundle.price.clone();
+        let mut platform_fee = BigUint::zero();
+        let nft_token_id = self.template_nft_token_id().get();
+        let mut nfts = ManagedVec::new();
+
+        for (index, nft_nonce) in bundle.nft_nonces.iter().enumerate() {
+            let template_price = if index + 1 == template_count {
+                remaining.clone()
+            } else {
+                share.clone()
+            };
+            remaining -= &template_price;
+
+            let proceeds = self.settle_sale(
+                &bundle.seller,
+                nft_nonce,
+                &bundle.payment_token,
+                &template_price,
+            );
+            platform_fee += proceeds.platform_fee;
+
+            self.record_template_sale(&buyer, nft_nonce);
+            self.check_popular_template_achievement(nft_nonce);
+
+            nfts.push(EsdtTokenPayment::new(
+                nft_token_id.clone(),
+                nft_nonce,
+                BigUint::from(1u64),
+            ));
+        }
+
+        self.send().direct_multi(&buyer, &nfts);
+
+        self.close_bundle(bundle_id);
+
+        self.check_first_sale_achievement(&bundle.seller);
+
+        self.bundle_purchased_event(bundle_id, buyer, bundle.seller, bundle.price, platform_fee);
+    }
+
+    #[endpoint(cancelBundle)]
+    fn cancel_bundle(&self, bundle_id: u64) {
+        require!(!self.bundles(bundle_id).is_empty(), "Bundle not active");
+        let bundle = self.bundles(bundle_id).get();
+        require!(
+            bundle.seller == self.blockchain().get_caller(),
+            "Only the seller can cancel"
+        );
+
+        let nft_token_id = self.template_nft_token_id().get();
+        let mut nfts = ManagedVec::new();
+        for nft_nonce in bundle.nft_nonces.iter() {
+            nfts.push(EsdtTokenPayment::new(
+                nft_token_id.clone(),
+                nft_nonce,
+                BigUint::from(1u64),
+            ));
+        }
+        self.send().direct_multi(&bundle.seller, &nfts);
+
+        self.close_bundle(bundle_id);
+    }
+
+    fn close_bundle(&self, bundle_id: u64) {
+        self.bundles(bundle_id).clear();
+        self.active_bundle_ids().remove(&bundle_id);
+    }
+
+    // ========== ENGLISH AUCTIONS ==========
+
+    /// Puts a template NFT up for auction until `end_time`. Bids are escrowed
+    /// by the contract and the previous highest bidder is refunded when outbid.
+    #[payable("*")]
+    #[endpoint(createAuction)]
+    fn create_auction(
+        &self,
+        nft_nonce: u64,
+        starting_price: BigUint,
+        min_bid_increment: BigUint,
+        end_time: u64,
+        payment_token: OptionalValue<EgldOrEsdtTokenIdentifier>,
+    ) -> u64 {
+        self.require_not_paused(Subsystem::Marketplace);
+        self.require_caller_allowed();
+        self.require_template_nft_payment(nft_nonce);
+        require!(
+            starting_price > BigUint::zero(),
+            "Starting price must be greater than 0"
+        );
+        require!(
+            min_bid_increment > BigUint::zero(),
+            "Bid increment must be greater than 0"
+        );
+        require!(
+            end_time > self.current_timestamp(),
+            "End time must be in the future"
+        );
+
+        let payment_token = self.resolve_payment_token(payment_token);
+        let caller = self.blockchain().get_caller();
+        let auction_id = self.next_auction_id().get();
+
+        let auction = Auction {
+            id: auction_id,
+            seller: caller.clone(),
+            nft_nonce,
+            payment_token,
+            starting_price: starting_price.clone(),
+            min_bid_increment,
+            end_time,
+            highest_bid: BigUint::zero(),
+            highest_bidder: ManagedAddress::zero(),
+            active: true,
+        };
+
+        self.auctions(auction_id).set(&auction);
+        self.next_auction_id().set(auction_id + 1);
+        self.active_auctions().insert(auction_id);
+
+        self.auction_created_event(auction_id, nft_nonce, caller, end_time, starting_price);
+
+        auction_id
+    }
+
+    #[payable("*")]
+    #[endpoint(placeBid)]
+    fn place_bid(&self, auction_id: u64) {
+        self.require_not_paused(Subsystem::Marketplace);
+        self.require_caller_allowed();
+        let payment = self.call_value().egld_or_single_esdt();
+        require!(!self.auctions(auction_id).is_empty(), "Auction not found");
+        let mut auction = self.auctions(auction_id).get();
+
+        require!(auction.active, "Auction not active");
+        self.require_template_not_flagged(auction.nft_nonce);
+        let now = self.current_timestamp();
+        require!(now < auction.end_time, "Auction has ended");
+
+        let bidder = self.blockchain().get_caller();
+        require!(bidder != auction.seller, "Cannot bid on your own auction");
+        require!(
+            payment.token_identifier == auction.payment_token,
+            "Wrong payment token"
+        );
+
+        let has_bids = !auction.highest_bidder.is_zero();
+        let min_bid = if has_bids {
+            &auction.highest_bid + &auction.min_bid_increment
+        } else {
+            auction.starting_price.clone()
+        };
+        require!(payment.amount >= min_bid, "Bid too low");
+
+        // Refund the previous highest bidder. Contracts claim theirs, so a
+        // bidder rejecting transfers cannot block higher bids.
+        if has_bids {
+            if self.blockchain().is_smart_contract(&auction.highest_bidder) {
+                self.credit_proceeds(
+                    &auction.highest_bidder,
+                    &auction.payment_token,
+                    &auction.highest_bid,
+                );
+            } else {
+                self.send().direct(
+                    &auction.highest_bidder,
+                    &auction.payment_token,
+                    0,
+                    &auction.highest_bid,
+                );
+            }
+        }
+
+        auction.highest_bid = payment.amount.clone();
+        auction.highest_bidder = bidder.clone();
+
+        // Anti-sniping: late bids extend the auction
+        if auction.end_time - now < AUCTION_EXTENSION_SECONDS {
+            auction.end_time = now + AUCTION_EXTENSION_SECONDS;
+        }
+
+        self.auctions(auction_id).set(&auction);
+        self.auction_bids(auction_id).push(&Bid {
+            bidder: bidder.clone(),
+            amount: payment.amount.clone(),
+            timestamp: now,
+        });
+
+        self.bid_placed_event(auction_id, bidder, auction.end_time, payment.amount);
+    }
+
+    /// Settles an auction once its end time has passed. Anyone can call it:
+    /// the winner receives the NFT and the seller the winning bid minus the
+    /// platform fee; without bids the NFT goes back to the seller.
+    /// Auctions with a winner wait for the marketplace to be unpaused;
+    /// auctions without bids can be ended while paused.
+    #[endpoint(endAuction)]
+    fn end_auction(&self, auction_id: u64) {
+        require!(!self.auctions(auction_id).is_empty(), "Auction not found");
+        let auction = self.auctions(auction_id).get();
+
+        require!(auction.active, "Auction not active");
+        require!(
+            self.current_timestamp() >= auction.end_time,
+            "Auction has not ended yet"
+        );
+        let flagged = self.is_template_flagged(auction.nft_nonce);
+        if !auction.highest_bidder.is_zero() && !flagged {
+            self.require_not_paused(Subsystem::Marketplace);
+        }
+
+        self.auctions(auction_id).update(|a| {
+            a.active = false;
+        });
+        self.active_auctions().swap_remove(&auction_id);
+
+        // Flagged templates are not sold: the highest bid is refunded
+        if !auction.highest_bidder.is_zero() && flagged {
+            self.credit_proceeds(
+                &auction.highest_bidder,
+                &auction.payment_token,
+                &auction.highest_bid,
+            );
+            self.send_template_nft(&auction.seller, auction.nft_nonce);
+            self.auction_cancelled_event(auction_id, auction.seller);
+            return;
+        }
+
+        if auction.highest_bidder.is_zero() {
+            self.send_template_nft(&auction.seller, auction.nft_nonce);
+            self.auction_ended_event(
+                auction_id,
+                ManagedAddress::zero(),
+                auction.seller,
+                BigUint::zero(),
+            );
+            return;
+        }
+
+        self.settle_sale(
+            &auction.seller,
+            auction.nft_nonce,
+            &auction.payment_token,
+            &auction.highest_bid,
+        );
+        self.send_template_nft(&auction.highest_bidder, auction.nft_nonce);
+
+        self.record_template_sale(&auction.highest_bidder, auction.nft_nonce);
+
+        self.check_first_sale_achievement(&auction.seller);
+        self.check_popular_template_achievement(auction.nft_nonce);
+
+        self.auction_ended_event(
+            auction_id,
+            auction.highest_bidder,
+            auction.seller,
+            auction.highest_bid,
+        );
+    }
+
+    /// Auctions can only be cancelled by the seller while nobody has bid yet.
+    #[endpoint(cancelAuction)]
+    fn cancel_auction(&self, auction_id: u64) {
+        require!(!self.auctions(auction_id).is_empty(), "Auction not found");
+        let auction = self.auctions(auction_id).get();
+
+        require!(auction.active, "Auction not active");
+        require!(
+            auction.seller == self.blockchain().get_caller(),
+            "Only the seller can cancel"
+        );
+        require!(
+            auction.highest_bidder.is_zero(),
+            "Cannot cancel an auction with bids"
+        );
+
+        self.auctions(auction_id).update(|a| {
+            a.active = false;
+        });
+        self.active_auctions().swap_remove(&auction_id);
+
+        self.send_template_nft(&auction.seller, auction.nft_nonce);
+
+        self.auction_cancelled_event(auction_id, auction.seller);
+    }
+
+    // ========== DUTCH AUCTIONS ==========
+
+    /// Lists a template NFT at a price that declines linearly from `start_price`
+    /// to `floor_price` over `duration` seconds, then stays at the floor.
+    #[payable("*")]
+    #[endpoint(createDutchAuction)]
+    fn create_dutch_auction(
+        &self,
+        nft_nonce: u64,
+        start_price: BigUint,
+        floor_price: BigUint,
+        duration: u64,
+        payment_token: OptionalValue<EgldOrEsdtTokenIdentifier>,
+    ) -> u64 {
+        self.require_not_paused(Subsystem::Marketplace);
+        self.require_caller_allowed();
+        self.require_template_nft_payment(nft_nonce);
+        require!(
+            floor_price > BigUint::zero(),
+            "Floor price must be greater than 0"
+        );
+        require!(
+            start_price > floor_price,
+            "Start price must be above floor price"
+        );
+        require!(duration > 0, "Duration must be greater than 0");
+
+        let payment_token = self.resolve_payment_token(payment_token);
+        let caller = self.blockchain().get_caller();
+        let auction_id = self.next_dutch_auction_id().get();
+        let start_time = self.current_timestamp();
+
+        let auction = DutchAuction {
+            id: auction_id,
+            seller: caller.clone(),
+            nft_nonce,
+            payment_token,
+            start_price: start_price.clone(),
+            floor_price,
+            start_time,
+            end_time: start_time + duration,
+            active: true,
+        };
+
+        self.dutch_auctions(auction_id).set(&auction);
+        self.next_dutch_auction_id().set(auction_id + 1);
+
+        self.dutch_auction_created_event(auction_id, nft_nonce, caller, start_price);
+
+        auction_id
+    }
+
+    #[payable("*")]
+    #[endpoint(buyDutchAuction)]
+    fn buy_dutch_auction(&self, auction_id: u64) {
+        self.require_not_paused(Subsystem::Marketplace);
+        self.require_caller_allowed();
+        let payment = self.call_value().egld_or_single_esdt();
+        require!(
+            !self.dutch_auctions(auction_id).is_empty(),
+            "Auction not found"
+        );
+        let auction = self.dutch_auctions(auction_id).get();
+
+        require!(auction.active, "Auction not active");
+        self.require_template_not_flagged(auction.nft_nonce);
+        require!(
+            payment.token_identifier == auction.payment_token,
+            "Wrong payment token"
+        );
+
+        let price = self.dutch_auction_price(&auction);
+        require!(payment.amount >= price, "Insufficient payment");
+
+        let buyer = self.blockchain().get_caller();
+        require!(buyer != auction.seller, "Cannot buy your own template");
+
+        self.dutch_auctions(auction_id).update(|a| {
+            a.active = false;
+        });
+
+        // Return any overpayment to the buyer
+        let excess = &payment.amount - &price;
+        self.send()
+            .direct_non_zero(&buyer, &auction.payment_token, 0, &excess);
+
+        self.settle_sale(
+            &auction.seller,
+            auction.nft_nonce,
+            &auction.payment_token,
+            &price,
+        );
+        self.send_template_nft(&buyer, auction.nft_nonce);
+
+        self.record_template_sale(&buyer, auction.nft_nonce);
+
+        self.check_first_sale_achievement(&auction.seller);
+        self.check_popular_template_achievement(auction.nft_nonce);
+
+        self.dutch_auction_purchased_event(auction_id, buyer, auction.seller, price);
+    }
+
+    #[endpoint(cancelDutchAuction)]
+    fn cancel_dutch_auction(&self, auction_id: u64) {
+        require!(
+            !self.dutch_auctions(auction_id).is_empty(),
+            "Auction not found"
+        );
+        let auction = self.dutch_auctions(auction_id).get();
+
+        require!(auction.active, "Auction not active");
+        require!(
+            auction.seller == self.blockchain().get_caller(),
+            "Only the seller can cancel"
+        );
+
+        self.dutch_auctions(auction_id).update(|a| {
+            a.active = false;
+        });
+
+        self.send_template_nft(&auction.seller, auction.nft_nonce);
+    }
+
+    fn dutch_auction_price(&self, auction: &DutchAuction<Self::Api>) -> BigUint {
+        let now = self.current_timestamp();
+        if now >= auction.end_time {
+            return auction.floor_price.clone();
+        }
+
+        let elapsed = now - auction.start_time;
+        let duration = auction.end_time - auction.start_time;
+        let decay = (&auction.start_price - &auction.floor_price) * elapsed / duration;
+
+        &auction.start_price - &decay
+    }
+
+    // ========== OFFERS ==========
+
+    /// Escrows a payment as an offer on any template NFT, listed or not.
+    /// The offer stays open until `expires_at`, unless accepted or withdrawn.
+    #[payable("*")]
+    #[endpoint(makeOffer)]
+    fn make_offer(&self, nft_nonce: u64, expires_at: u64) -> u64 {
+        self.require_not_paused(Subsystem::Marketplace);
+        self.require_caller_allowed();
+        self.require_template_not_flagged(nft_nonce);
+        let payment = self.call_value().egld_or_single_esdt();
+        require!(payment.token_nonce == 0, "Invalid payment token");
+        require!(
+            payment.amount > BigUint::zero(),
+            "Offer must be greater than 0"
+        );
+        require!(
+            expires_at > self.current_timestamp(),
+            "Expiry must be in the future"
+        );
+        let payment_token =
+            self.resolve_payment_token(OptionalValue::Some(payment.token_identifier));
+
+        let caller = self.blockchain().get_caller();
+        let offer_id = self.next_offer_id().get();
+
+        let offer = Offer {
+            id: offer_id,
+            buyer: caller.clone(),
+            nft_nonce,
+            payment_token,
+            amount: payment.amount.clone(),
+            expires_at,
+        };
+
+        self.offers(offer_id).set(&offer);
+        self.next_offer_id().set(offer_id + 1);
+        self.nonce_offers(nft_nonce).insert(offer_id);
+        self.buyer_offers(&caller).insert(offer_id);
+
+        self.offer_made_event(offer_id, nft_nonce, caller, payment.amount);
+
+        offer_id
+    }
+
+    /// Called by the current holder of the template, sending the NFT along.
+    #[payable("*")]
+    #[endpoint(acceptOffer)]
+    fn accept_offer(&self, offer_id: u64) {
+        self.require_not_paused(Subsystem::Marketplace);
+        self.require_caller_allowed();
+        require!(!self.offers(offer_id).is_empty(), "Offer not found");
+        let offer = self.offers(offer_id).get();
+
+        self.require_template_nft_payment(offer.nft_nonce);
+        require!(
+            self.current_timestamp() < offer.expires_at,
+            "Offer has expired"
+        );
+
+        let seller = self.blockchain().get_caller();
+        require!(seller != offer.buyer, "Cannot accept your own offer");
+
+        self.remove_offer(&offer);
+
+        self.settle_sale(
+            &seller,
+            offer.nft_nonce,
+            &offer.payment_token,
+            &offer.amount,
+        );
+        self.send_template_nft(&offer.buyer, offer.nft_nonce);
+
+        self.record_template_sale(&offer.buyer, offer.nft_nonce);
+
+        self.check_first_sale_achievement(&seller);
+        self.check_popular_template_achievement(offer.nft_nonce);
+
+        self.offer_accepted_event(offer_id, offer.buyer, seller, offer.amount);
+    }
+
+    /// The buyer can withdraw an offer at any time; once expired, anyone can
+    /// trigger the refund.
+    #[endpoint(withdrawOffer)]
+    fn withdraw_offer(&self, offer_id: u64) {
+        require!(!self.offers(offer_id).is_empty(), "Offer not found");
+        let offer = self.offers(offer_id).get();
+
+        let caller = self.blockchain().get_caller();
+        require!(
+            caller == offer.buyer || self.current_timestamp() >= offer.expires_at,
+            "Only the buyer can withdraw an active offer"
+        );
+
+        self.remove_offer(&offer);
+
+        self.send()
+            .direct(&offer.buyer, &offer.payment_token, 0, &offer.amount);
+
+        self.offer_withdrawn_event(offer_id, offer.buyer, offer.amount);
+    }
+
+    fn remove_offer(&self, offer: &Offer<Self::Api>) {
+        self.offers(offer.id).clear();
+        self.nonce_offers(offer.nft_nonce).swap_remove(&offer.id);
+        self.buyer_offers(&offer.buyer).swap_remove(&offer.id);
+    }
+
+    // ========== RENTALS ==========
+
+    /// Escrows a template NFT so others can pay for time-limited access to it.
+    #[payable("*")]
+    #[endpoint(listForRent)]
+    fn list_for_rent(
+        &self,
+        nft_nonce: u64,
+        price_per_day: BigUint,
+        max_days: u64,
+        payment_token: OptionalValue<EgldOrEsdtTokenIdentifier>,
+    ) {
+        self.require_not_paused(Subsystem::Marketplace);
+        self.require_caller_allowed();
+        self.require_template_nft_payment(nft_nonce);
+        require!(
+            price_per_day > BigUint::zero(),
+            "Price must be greater than 0"
+        );
+        require!(max_days > 0, "Max days must be greater than 0");
+
+        let payment_token = self.resolve_payment_token(payment_token);
+        let caller = self.blockchain().get_caller();
+
+        let rental = RentalOffer {
+            nft_nonce,
+            owner: caller.clone(),
+            payment_token,
+            price_per_day: price_per_day.clone(),
+            max_days,
+            rented_until: 0,
+            available: true,
+        };
+        self.rental_offers(nft_nonce).set(&rental);
+
+        self.template_listed_for_rent_event(nft_nonce, caller, price_per_day);
+    }
+
+    /// Buys `days` of access to a rentable template. Renting again extends
+    /// the current access period.
+    #[payable("*")]
+    #[endpoint(rentTemplate)]
+    fn rent_template(&self, nft_nonce: u64, days: u64) {
+        self.require_not_paused(Subsystem::Marketplace);
+        self.require_caller_allowed();
+        self.require_template_not_flagged(nft_nonce);
+        let payment = self.call_value().egld_or_single_esdt();
+        require!(
+            !self.rental_offers(nft_nonce).is_empty(),
+            "Template not for rent"
+        );
+        let mut rental = self.rental_offers(nft_nonce).get();
+
+        require!(rental.available, "Template not for rent");
+        require!(
+            days > 0 && days <= rental.max_days,
+            "Invalid rental duration"
+        );
+        require!(
+            payment.token_identifier == rental.payment_token,
+            "Wrong payment token"
+        );
+        let price = &rental.price_per_day * days;
+        require!(payment.amount >= price, "Insufficient payment");
+
+        let renter = self.blockchain().get_caller();
+        require!(renter != rental.owner, "Cannot rent your own template");
+
+        let now = self.current_timestamp();
+        let access_mapper = self.template_access(&renter, nft_nonce);
+        let access_start = core::cmp::max(access_mapper.get(), now);
+        let access_until = access_start + days * SECONDS_PER_DAY;
+        access_mapper.set(access_until);
+
+        if access_until > rental.rented_until {
+            rental.rented_until = access_until;
+            self.rental_offers(nft_nonce).set(&rental);
+        }
+
+        // Return any overpayment to the renter
+        let excess = &payment.amount - &price;
+        self.send()
+            .direct_non_zero(&renter, &rental.payment_token, 0, &excess);
+
+        self.settle_sale(&rental.owner, nft_nonce, &rental.payment_token, &price);
+
+        self.template_rented_event(nft_nonce, renter, access_until, price);
+    }
+
+    /// Stops or resumes new rentals; existing rentals are not affected.
+    #[endpoint(setRentalAvailability)]
+    fn set_rental_availability(&self, nft_nonce: u64, available: bool) {
+        require!(
+            !self.rental_offers(nft_nonce).is_empty(),
+            "Template not for rent"
+        );
+        self.rental_offers(nft_nonce).update(|rental| {
+            require!(
+                rental.owner == self.blockchain().get_caller(),
+                "Only the owner can update the rental"
+            );
+            rental.available = available;
+        });
+    }
+
+    /// Returns the NFT to its owner once every rental has run out.
+    #[endpoint(withdrawRentedTemplate)]
+    fn withdraw_rented_template(&self, nft_nonce: u64) {
+        require!(
+            !self.rental_offers(nft_nonce).is_empty(),
+            "Template not for rent"
+        );
+        let rental = self.rental_offers(nft_nonce).get();
+
+        require!(
+            rental.owner == self.blockchain().get_caller(),
+            "Only the owner can withdraw"
+        );
+        require!(
+            self.current_timestamp() >= rental.rented_until,
+            "Template still has active rentals"
+        );
+
+        self.rental_offers(nft_nonce).clear();
+        self.send_template_nft(&rental.owner, nft_nonce);
+    }
+
+    // ========== RATING SYSTEM ==========
+
+    /// Only buyers, renters with a running rental and current holders can
+    /// rate a template. Holders that got the NFT outside the marketplace prove
+    /// it by attaching the NFT, which is sent straight back.
+    /// `review_cid` optionally points to a written review stored on IPFS.
+    #[payable("*")]
+    #[endpoint(rateTemplate)]
+    fn rate_template(&self, nft_nonce: u64, rating: u8, review_cid: OptionalValue<ManagedBuffer>) {
+        self.require_not_paused(Subsystem::Ratings);
+        self.require_caller_allowed();
+        require!((1..=5).contains(&rating), "Rating must be 1-5");
+        require!(self.is_minted_template(nft_nonce), "Template not found");
+
+        let caller = self.blockchain().get_caller();
+        let generation_id = self.template_generation(nft_nonce).get();
+        require!(
+            self.generations(generation_id).get().creator != caller,
+            "Cannot rate your own template"
+        );
+        require!(
+            self.is_verified_rater(&caller, nft_nonce),
+            "Only owners or renters can rate"
+        );
+
+        // Existing ratings are changed through updateRating
+        let user_rating_key = self.user_template_rating(&caller, nft_nonce);
+        require!(user_rating_key.is_empty(), "Already rated this template");
+
+        // Store user's rating
+        user_rating_key.set(rating);
+
+        // Update template aggregate rating
+        self.template_ratings(nft_nonce).update(|attrs| {
+            attrs.total_rating += rating as u64;
+            attrs.rating_count += 1;
+        });
+        self.global_rating_total()
+            .update(|total| *total += rating as u64);
+        self.global_rating_count().update(|count| *count += 1);
+        let creator = self.template_creator(nft_nonce);
+        self.creator_ratings(&creator).update(|attrs| {
+            attrs.total_rating += rating as u64;
+            attrs.rating_count += 1;
+        });
+        self.refresh_leaderboard(nft_nonce);
+        self.refresh_creator_ranking(&creator);
+
+        let review = Review {
+            rater: caller.clone(),
+            rating,
+            review_cid: review_cid.into_option().unwrap_or_default(),
+            timestamp: self.current_timestamp(),
+        };
+        self.template_reviews(&caller, nft_nonce).set(&review);
+        self.template_reviewers(nft_nonce).insert(caller.clone());
+
+        self.template_rated_event(nft_nonce, caller, rating);
+    }
+
+    /// Changes an existing rating. The review is kept unless a new
+    /// `review_cid` is given.
+    #[endpoint(updateRating)]
+    fn update_rating(&self, nft_nonce: u64, rating: u8, review_cid: OptionalValue<ManagedBuffer>) {
+        self.require_not_paused(Subsystem::Ratings);
+        self.require_caller_allowed();
+        require!((1..=5).contains(&rating), "Rating must be 1-5");
+
+        let caller = self.blockchain().get_caller();
+        let user_rating_key = self.user_template_rating(&caller, nft_nonce);
+        require!(!user_rating_key.is_empty(), "Template not rated yet");
+
+        let old_rating = user_rating_key.get();
+        user_rating_key.set(rating);
+
+        self.template_ratings(nft_nonce).update(|attrs| {
+            attrs.total_rating = attrs.total_rating - old_rating as u64 + rating as u64;
+        });
+        self.global_rating_total()
+            .update(|total| *total = *total - old_rating as u64 + rating as u64);
+        let creator = self.template_creator(nft_nonce);
+        self.creator_ratings(&creator).update(|attrs| {
+            attrs.total_rating = attrs.total_rating - old_rating as u64 + rating as u64;
+        });
+        self.refresh_leaderboard(nft_nonce);
+        self.refresh_creator_ranking(&creator);
+
+        self.template_reviews(&caller, nft_nonce).update(|review| {
+            review.rating = rating;
+            if let Some(review_cid) = review_cid.into_option() {
+                review.review_cid = review_cid;
+            }
+            review.timestamp = self.current_timestamp();
+        });
+
+        self.rating_updated_event(nft_nonce, caller, old_rating, rating);
+    }
+
+    #[endpoint(removeRating)]
+    fn remove_rating(&self, nft_nonce: u64) {
+        self.require_not_paused(Subsystem::Ratings);
+
+        let caller = self.blockchain().get_caller();
+        let user_rating_key = self.user_template_rating(&caller, nft_nonce);
+        require!(!user_rating_key.is_empty(), "Template not rated yet");
+
+        let old_rating = user_rating_key.take();
+        self.template_ratings(nft_nonce).update(|attrs| {
+            attrs.total_rating -= old_rating as u64;
+            attrs.rating_count -= 1;
+        });
+        self.global_rating_total()
+            .update(|total| *total -= old_rating as u64);
+        self.global_rating_count().update(|count| *count -= 1);
+        let creator = self.template_creator(nft_nonce);
+        self.creator_ratings(&creator).update(|attrs| {
+            attrs.total_rating -= old_rating as u64;
+            attrs.rating_count -= 1;
+        });
+        self.refresh_leaderboard(nft_nonce);
+        self.refresh_creator_ranking(&creator);
+
+        // A later rating starts with a visible review again
+        self.template_reviews(&caller, nft_nonce).clear();
+        self.review_hidden(&caller, nft_nonce).clear();
+        self.template_reviewers(nft_nonce).remove(&caller);
+
+        self.rating_removed_event(nft_nonce, caller, old_rating);
+    }
+
+    /// Creator feedback on a completed generation, minted or not. Aggregated
+    /// per category and per generator version to track output quality.
+    #[endpoint(rateGeneration)]
+    fn rate_generation(
+        &self,
+        generation_id: u64,
+        quality: u8,
+        compiled_as_is: bool,
+        needed_edits: bool,
+    ) {
+        self.require_not_paused(Subsystem::Ratings);
+        self.require_caller_allowed();
+        require!((1..=5).contains(&quality), "Rating must be 1-5");
+        require!(
+            !self.generations(generation_id).is_empty(),
+            "Generation not found"
+        );
+
+        let caller = self.blockchain().get_caller();
+        let generation = self.generations(generation_id).get();
+        require!(generation.creator == caller, "Not the generation creator");
+        require!(
+            generation.status == GenerationStatus::Completed,
+            "Generation not completed"
+        );
+
+        let feedback_mapper = self.generation_feedback(generation_id);
+        require!(feedback_mapper.is_empty(), "Generation already rated");
+
+        let feedback = GenerationFeedback {
+            quality,
+            compiled_as_is,
+            needed_edits,
+            timestamp: self.current_timestamp(),
+        };
+        feedback_mapper.set(&feedback);
+
+        self.category_generation_stats(&generation.category)
+            .update(|stats| stats.record(&feedback));
+        let version_mapper = self.generation_generator_version(generation_id);
+        if !version_mapper.is_empty() {
+            self.generator_version_stats(&version_mapper.get())
+                .update(|stats| stats.record(&feedback));
+        }
+
+        self.generation_rated_event(generation_id, caller, quality);
+    }
+
+    fn template_creator(&self, nft_nonce: u64) -> ManagedAddress {
+        let generation_id = self.template_generation(nft_nonce).get();
+        self.generations(generation_id).get().creator
+    }
+
+    /// Read from the generation index rather than the token attributes, which
+    /// are gone once the contract no longer holds the template
+    fn template_category(&self, nft_nonce: u64) -> ManagedBuffer {
+        let generation_id = self.template_generation(nft_nonce).get();
+        self.generations(generation_id).get().category
+    }
+
+    /// Template nonces are assigned sequentially by this contract
+    fn is_minted_template(&self, nft_nonce: u64) -> bool {
+        let nft_token_id = self.template_nft_token_id().get();
+        let last_nonce = self
+            .blockchain()
+            .get_current_esdt_nft_nonce(&self.blockchain().get_sc_address(), &nft_token_id);
+        nft_nonce > 0 && nft_nonce <= last_nonce
+    }
+
+    /// Any attached payment must be the template itself and is always returned
+    fn is_verified_rater(&self, user: &ManagedAddress, nft_nonce: u64) -> bool {
+        let payment = self.call_value().egld_or_single_esdt();
+        if payment.amount == 0u64 {
+            return self.has_used_template(user, nft_nonce);
+        }
+
+        let nft_token_id = self.template_nft_token_id().get();
+        require!(
+            payment.token_identifier == EgldOrEsdtTokenIdentifier::esdt(nft_token_id)
+                && payment.token_nonce == nft_nonce,
+            "Wrong token"
+        );
+        self.send_template_nft(user, nft_nonce);
+
+        true
+    }
+
+    /// Bought the template through the marketplace or is renting it right now
+    fn has_used_template(&self, user: &ManagedAddress, nft_nonce: u64) -> bool {
+        self.template_acquired(user, nft_nonce).get()
+            || self.template_access(user, nft_nonce).get() > self.current_timestamp()
+    }
+
+    // ========== LEADERBOARDS ==========
+
+    /// Weighted rating scaled by 100: the template's votes plus
+    /// `RATING_PRIOR_WEIGHT` votes at the platform-wide mean.
+    fn bayesian_score(&self, rating: &TemplateRatingInfo) -> u64 {
+        let global_count = self.global_rating_count().get();
+        let prior_mean = (self.global_rating_total().get() * 100)
+            .checked_div(global_count)
+            .unwrap_or(DEFAULT_PRIOR_MEAN);
+
+        (RATING_PRIOR_WEIGHT * prior_mean + rating.total_rating * 100)
+            / (RATING_PRIOR_WEIGHT + rating.rating_count)
+    }
+
+    /// Re-ranks a template's category leaderboard. Every entry is rescored,
+    /// since the platform-wide mean behind the scores moves with each rating.
+    /// Unrated and flagged templates are dropped from it.
+    fn refresh_leaderboard(&self, nft_nonce: u64) {
+        let leaderboard = self.category_leaderboard(&self.template_category(nft_nonce));
+
+        let mut nonces = ManagedVec::<Self::Api, u64>::new();
+        for entry in leaderboard.get().into_iter() {
+            if entry.nft_nonce != nft_nonce {
+                nonces.push(entry.nft_nonce);
+            }
+        }
+        nonces.push(nft_nonce);
+
+        let mut entries = ManagedVec::<Self::Api, LeaderboardEntry>::new();
+        for nonce in nonces.into_iter() {
+            let rating = self.template_ratings(nonce).get();
+            if rating.rating_count == 0 || self.is_template_flagged(nonce) {
+                continue;
+            }
+            let mut candidate = Some(LeaderboardEntry {
+                nft_nonce: nonce,
+                score: self.bayesian_score(&rating),
+                uses: self.template_uses(nonce).get(),
+            });
+
+            let mut ranked = ManagedVec::new();
+            for entry in entries.into_iter() {
+                if candidate
+                    .as_ref()
+                    .is_some_and(|candidate| candidate.ranks_above(&entry))
+                {
+                    ranked.push(candidate.take().unwrap());
+                }
+                ranked.push(entry);
+            }
+            if let Some(candidate) = candidate {
+                ranked.push(candidate);
+            }
+            entries = ranked;
+        }
+
+        if entries.len() > LEADERBOARD_SIZE {
+            entries = entries.slice(0, LEADERBOARD_SIZE).unwrap_or_default();
+        }
+        leaderboard.set(entries);
+    }
+
+    /// Weighted creator reputation: the bayesian score of all ratings on the
+    /// creator's templates plus points for mints, sales and achievements.
+    fn creator_reputation(&self, creator: &ManagedAddress) -> u64 {
+        let rating = self.creator_ratings(creator).get();
+        let rating_score = if rating.rating_count > 0 {
+            self.bayesian_score(&rating)
+        } else {
+            0
+        };
+
+        rating_score
+            + self.creator_minted_count(creator).get() * REPUTATION_PER_MINT
+            + self.creator_sales_count(creator).get() * REPUTATION_PER_SALE
+            + self.user_achievements(creator).len() as u64 * REPUTATION_PER_ACHIEVEMENT
+    }
+
+    /// Re-ranks the top creators list, recomputing every entry's reputation
+    /// since the rating part of it moves with the platform-wide mean.
+    fn refresh_creator_ranking(&self, creator: &ManagedAddress) {
+        let mut creators = ManagedVec::<Self::Api, ManagedAddress>::new();
+        for entry in self.top_creators().get().into_iter() {
+            if &entry.creator != creator {
+                creators.push(entry.creator);
+            }
+        }
+        creators.push(creator.clone());
+
+        let mut entries = ManagedVec::<Self::Api, CreatorRankEntry<Self::Api>>::new();
+        for creator in creators.into_iter() {
+            let mut candidate = Some(CreatorRankEntry {
+                reputation: self.creator_reputation(&creator),
+                creator,
+            });
+
+            let mut ranked = ManagedVec::new();
+            for entry in entries.into_iter() {
+                if candidate
+                    .as_ref()
+                    .is_some_and(|candidate| candidate.reputation > entry.reputation)
+                {
+                    ranked.push(candidate.take().unwrap());
+                }
+                ranked.push(entry);
+            }
+            if let Some(candidate) = candidate {
+                ranked.push(candidate);
+            }
+            entries = ranked;
+        }
+
+        if entries.len() > LEADERBOARD_SIZE {
+            entries = entries.slice(0, LEADERBOARD_SIZE).unwrap_or_default();
+        }
+        self.top_creators().set(entries);
+    }
+
+    // ========== ACHIEVEMENTS (no NFT minting) ==========
+
+    /// Achievements are stored once per user and announced through an event
+    fn award_achievement(&self, user: &ManagedAddress, achievement: &[u8]) {
+        let achievement = ManagedBuffer::from(achievement);
+        if self.user_achievements(user).insert(achievement.clone()) {
+            self.achievement_earned_event(user.clone(), achievement);
+            self.refresh_creator_ranking(user);
+        }
+    }
+
+    fn check_first_generation_achievement(&self, user: &ManagedAddress) {
+        let count = self.user_generation_count(user).get();
+        if count == 1 {
+            self.award_achievement(user, b"First Generation");
+        }
+    }
+
+    fn check_first_sale_achievement(&self, user: &ManagedAddress) {
+        self.award_achievement(user, b"First Sale");
+    }
+
+    fn check_popular_template_achievement(&self, nft_nonce: u64) {
+        let uses = self.template_uses(nft_nonce).get();
+        if uses == 10 {
+            self.achievement_earned_event(
+                ManagedAddress::zero(), // Template-based, not user-based
+                ManagedBuffer::from(b"Popular Template"),
+            );
+        }
+    }
+
+    // ========== MODERATION ==========
+
+    #[endpoint(hideReview)]
+    fn hide_review(&self, nft_nonce: u64, rater: ManagedAddress, hidden: bool) {
+        self.require_role(Role::Moderator);
+        require!(
+            !self.template_reviews(&rater, nft_nonce).is_empty(),
+            "Review not found"
+        );
+
+        self.review_hidden(&rater, nft_nonce).set(hidden);
+        self.review_hidden_event(nft_nonce, rater, hidden);
+    }
+
+    /// Flagged templates can no longer be listed, bid on or bought.
+    #[endpoint(flagTemplate)]
+    fn flag_template(&self, nft_nonce: u64, reason_cid: ManagedBuffer) {
+        self.require_role(Role::Moderator);
+        require!(self.is_minted_template(nft_nonce), "Template not found");
+
+        self.flagged_templates().insert(nft_nonce);
+        self.template_flag_reason(nft_nonce).set(&reason_cid);
+        self.refresh_leaderboard(nft_nonce);
+        self.template_flagged_event(nft_nonce, self.blockchain().get_caller(), reason_cid);
+    }
+
+    #[endpoint(unflagTemplate)]
+    fn unflag_template(&self, nft_nonce: u64) {
+        self.require_role(Role::Moderator);
+        require!(
+            self.flagged_templates().swap_remove(&nft_nonce),
+            "Template not flagged"
+        );
+
+        self.template_flag_reason(nft_nonce).clear();
+        self.refresh_leaderboard(nft_nonce);
+        self.template_unflagged_event(nft_nonce, self.blockchain().get_caller());
+    }
+
+    /// Reports a template to the moderators. The stake is returned if the
+    /// report is upheld and kept as a fee if it is dismissed.
+    #[payable("EGLD")]
+    #[endpoint(reportTemplate)]
+    fn report_template(&self, nft_nonce: u64, reason_cid: ManagedBuffer) -> u64 {
+        self.require_caller_allowed();
+        let stake = self.call_value().egld().clone();
+        require!(
+            stake == self.report_stake().get(),
+            "Wrong report stake amount"
+        );
+        require!(self.is_minted_template(nft_nonce), "Template not found");
+        require!(!reason_cid.is_empty(), "Reason is required");
+
+        let caller = self.blockchain().get_caller();
+        let report_id = self.next_report_id().get();
+
+        let report = Report {
+            id: report_id,
+            reporter: caller.clone(),
+            nft_nonce,
+            reason_cid,
+            stake,
+            timestamp: self.current_timestamp(),
+            status: ReportStatus::Open,
+        };
+
+        self.reports(report_id).set(&report);
+        self.next_report_id().set(report_id + 1);
+        self.open_report_ids().insert(report_id);
+
+        self.template_reported_event(report_id, nft_nonce, caller);
+
+        report_id
+    }
+
+    #[endpoint(resolveReport)]
+    fn resolve_report(&self, report_id: u64, upheld: bool) {
+        self.require_role(Role::Moderator);
+        require!(!self.reports(report_id).is_empty(), "Report not found");
+        let mut report = self.reports(report_id).get();
+        require!(
+            report.status == ReportStatus::Open,
+            "Report already resolved"
+        );
+
+        if upheld {
+            report.status = ReportStatus::Upheld;
+            self.send()
+                .direct_non_zero_egld(&report.reporter, &report.stake);
+        } else {
+            report.status = ReportStatus::Dismissed;
+            self.accrue_fees(
+                FeeSource::Slashed,
+                &EgldOrEsdtTokenIdentifier::egld(),
+                &report.stake,
+            );
+        }
+
+        self.reports(report_id).set(&report);
+        self.open_report_ids().remove(&report_id);
+
+        self.report_resolved_event(report_id, self.blockchain().get_caller(), upheld);
+    }
+
+    /// Keeps the deposit of an abusive generation request as a fee
+    #[endpoint(slashGenerationDeposit)]
+    fn slash_generation_deposit(&self, generation_id: u64) {
+        self.require_role(Role::Moderator);
+        let deposit = self.generation_deposits(generation_id).take();
+        require!(deposit > 0u64, "No deposit held for generation");
+
+        self.accrue_fees(
+            FeeSource::Slashed,
+            &EgldOrEsdtTokenIdentifier::egld(),
+            &deposit,
+        );
+
+        self.generation_deposit_slashed_event(
+            generation_id,
+            self.blockchain().get_caller(),
+            deposit,
+        );
+    }
+
+    #[endpoint(setReportStake)]
+    fn set_report_stake(&self, stake: BigUint) {
+        self.require_role(Role::Admin);
+        self.report_stake().set(stake);
+    }
+
+    fn is_template_flagged(&self, nft_nonce: u64) -> bool {
+        self.flagged_templates().contains(&nft_nonce)
+    }
+
+    fn require_template_not_flagged(&self, nft_nonce: u64) {
+        require!(
+            !self.is_template_flagged(nft_nonce),
+            "Template flagged by moderators"
+        );
+    }
+
+    // ========== BLOCKLIST & ALLOWLIST ==========
+
+    #[endpoint(blockAddresses)]
+    fn block_addresses(&self, addresses: MultiValueEncoded<ManagedAddress>) {
+        self.require_role(Role::Moderator);
+        for address in addresses {
+            if self.blocked_addresses().insert(address.clone()) {
+                self.blocklist_changed_event(address, true);
+            }
+        }
+    }
+
+    #[endpoint(unblockAddresses)]
+    fn unblock_addresses(&self, addresses: MultiValueEncoded<ManagedAddress>) {
+        self.require_role(Role::Moderator);
+        for address in addresses {
+            if self.blocked_addresses().swap_remove(&address) {
+                self.blocklist_changed_event(address, false);
+            }
+        }
+    }
+
+    /// In allowlist-only mode (e.g. closed betas) only allowlisted addresses
+    /// can use the contract. The blocklist applies in both modes.
+    #[endpoint(setAllowlistOnly)]
+    fn set_allowlist_only(&self, enabled: bool) {
+        self.require_role(Role::Admin);
+        self.allowlist_only().set(enabled);
+        self.allowlist_mode_changed_event(enabled);
+    }
+
+    #[endpoint(addToAllowlist)]
+    fn add_to_allowlist(&self, addresses: MultiValueEncoded<ManagedAddress>) {
+        self.require_role(Role::Admin);
+        for address in addresses {
+            if self.allowlisted_addresses().insert(address.clone()) {
+                self.allowlist_changed_event(address, true);
+            }
+        }
+    }
+
+    #[endpoint(removeFromAllowlist)]
+    fn remove_from_allowlist(&self, addresses: MultiValueEncoded<ManagedAddress>) {
+        self.require_role(Role::Admin);
+        for address in addresses {
+            if self.allowlisted_addresses().swap_remove(&address) {
+                self.allowlist_changed_event(address, false);
+            }
+        }
+    }
+
+    fn is_address_allowed(&self, address: &ManagedAddress) -> bool {
+        self.address_denial(address).is_none()
+    }
+
+    /// Why an address may not use the platform, if it may not
+    fn address_denial(&self, address: &ManagedAddress) -> Option<&'static str> {
+        if self.blocked_addresses().contains(address) {
+            Some("Address is blocked")
+        } else if self.allowlist_only().get() && !self.allowlisted_addresses().contains(address) {
+            Some("Address is not allowlisted")
+        } else {
+            None
+        }
+    }
+
+    /// Checked by user-facing endpoints. Endpoints that only return a user's
+    /// own NFTs or funds stay open to blocked addresses.
+    fn require_caller_allowed(&self) {
+        if let Some(reason) = self.address_denial(&self.blockchain().get_caller()) {
+            sc_panic!(reason);
+        }
+    }
+
+    // ========== EMERGENCY PAUSE ==========
+
+    /// Halts one subsystem. Guardians can pause, only admins can unpause.
+    /// Endpoints returning escrowed NFTs or funds keep working while paused.
+    #[endpoint(pause)]
+    fn pause(&self, subsystem: Subsystem) {
+        self.require_role(Role::Guardian);
+
+        self.paused(subsystem).set(true);
+        self.pause_changed_event(subsystem, true);
+    }
+
+    #[endpoint(unpause)]
+    fn unpause(&self, subsystem: Subsystem) {
+        self.require_role(Role::Admin);
+        self.paused(subsystem).set(false);
+        self.pause_changed_event(subsystem, false);
+    }
+
+    fn require_not_paused(&self, subsystem: Subsystem) {
+        if !self.paused(subsystem).get() {
+            return;
+        }
+
+        match subsystem {
+            Subsystem::Generation => sc_panic!("Generation is paused"),
+            Subsystem::Minting => sc_panic!("Minting is paused"),
+            Subsystem::Marketplace => sc_panic!("Marketplace is paused"),
+            Subsystem::Ratings => sc_panic!("Ratings are paused"),
+        }
+    }
+
+    // ========== ACCESS CONTROL ==========
+
+    /// The owner can grant any role, admins every role except admin
+    #[endpoint(grantRole)]
+    fn grant_role(&self, role: Role, account: ManagedAddress) {
+        self.require_role_manager(role);
+        require!(
+            self.role_members(role).insert(account.clone()),
+            "Account already has role"
+        );
+        self.role_granted_event(role, account, self.blockchain().get_caller());
+    }
+
+    #[endpoint(revokeRole)]
+    fn revoke_role(&self, role: Role, account: ManagedAddress) {
+        self.require_role_manager(role);
+        require!(
+            self.role_members(role).swap_remove(&account),
+            "Account does not have role"
+        );
+        self.role_revoked_event(role, account, self.blockchain().get_caller());
+    }
+
+    /// Lets a role holder give up their own role
+    #[endpoint(renounceRole)]
+    fn renounce_role(&self, role: Role) {
+        let caller = self.blockchain().get_caller();
+        require!(
+            self.role_members(role).swap_remove(&caller),
+            "Account does not have role"
+        );
+        self.role_revoked_event(role, caller.clone(), caller);
+    }
+
+    /// The owner implicitly holds every role
+    fn has_role(&self, role: Role, account: &ManagedAddress) -> bool {
+        account == &self.blockchain().get_owner_address()
+            || self.role_members(role).contains(account)
+    }
+
+    fn require_role(&self, role: Role) {
+        if self.has_role(role, &self.blockchain().get_caller()) {
+            return;
+        }
+
+        match role {
+            Role::Admin => sc_panic!("Only owner or admin"),
+            Role::Oracle => sc_panic!("Only owner or oracle"),
+            Role::Moderator => sc_panic!("Only owner or moderator"),
+            Role::Treasurer => sc_panic!("Only owner or treasurer"),
+            Role::Guardian => sc_panic!("Only owner or guardian"),
+        }
+    }
+
+    fn require_role_manager(&self, role: Role) {
+        let caller = self.blockchain().get_caller();
+        if role == Role::Admin {
+            require!(
+                caller == self.blockchain().get_owner_address(),
+                "Only owner can manage admins"
+            );
+        } else {
+            self.require_role(Role::Admin);
+        }
+    }
+
+    // ========== ADMIN FUNCTIONS ==========
+
+    #[endpoint(addAcceptedPaymentToken)]
+    fn add_accepted_payment_token(&self, token_id: TokenIdentifier) {
+        self.require_role(Role::Admin);
+        require!(
+            token_id.is_valid_esdt_identifier(),
+            "Invalid token identifier"
+        );
+        self.accepted_payment_tokens().insert(token_id);
+    }
+
+    #[endpoint(removeAcceptedPaymentToken)]
+    fn remove_accepted_payment_token(&self, token_id: TokenIdentifier) {
+        self.require_role(Role::Admin);
+        self.accepted_payment_tokens().swap_remove(&token_id);
+    }
+
+    /// When enabled, seller proceeds and royalties are credited to a claimable
+    /// balance instead of being transferred inside the buyer's transaction.
+    #[endpoint(setPullPaymentsEnabled)]
+    fn set_pull_payments_enabled(&self, enabled: bool) {
+        self.require_role(Role::Admin);
+        self.pull_payments_enabled().set(enabled);
+    }
+
+    // ========== TIMELOCKED PARAMETERS ==========
+
+    /// Queues a change to the daily limit, minting fee, platform fee,
+    /// generation deposit or the timelock delay itself. It can be executed
+    /// once the delay has passed.
+    #[endpoint(proposeParameterChange)]
+    fn propose_parameter_change(&self, change: ParameterChange<Self::Api>) -> u64 {
+        self.require_role(Role::Admin);
+        self.validate_parameter_change(&change);
+
+        let change_id = self.next_parameter_change_id().get();
+        let executable_at = self.current_timestamp() + self.timelock_delay().get();
+
+        let pending = PendingParameterChange {
+            id: change_id,
+            change,
+            proposed_by: self.blockchain().get_caller(),
+            executable_at,
+        };
+
+        self.pending_parameter_changes(change_id).set(&pending);
+        self.next_parameter_change_id().set(change_id + 1);
+        self.pending_parameter_change_ids().insert(change_id);
+
+        self.parameter_change_proposed_event(change_id, executable_at, pending.change);
+
+        change_id
+    }
+
+    #[endpoint(executeParameterChange)]
+    fn execute_parameter_change(&self, change_id: u64) {
+        self.require_role(Role::Admin);
+        require!(
+            !self.pending_parameter_changes(change_id).is_empty(),
+            "Parameter change not found"
+        );
+        let pending = self.pending_parameter_changes(change_id).take();
+        require!(
+            self.current_timestamp() >= pending.executable_at,
+            "Timelock has not expired"
+        );
+        self.pending_parameter_change_ids().remove(&change_id);
+
+        match &pending.change {
+            ParameterChange::DailyLimit(limit) => self.daily_generation_limit().set(limit),
+            ParameterChange::MintingFee(fee) => self.nft_minting_fee().set(fee),
+            ParameterChange::PlatformFee(fee_bps) => self.platform_fee_percent().set(fee_bps),
+            ParameterChange::TimelockDelay(delay) => self.timelock_delay().set(delay),
+            ParameterChange::GenerationDeposit(deposit) => {
+                self.generation_deposit_amount().set(deposit)
+            }
+            ParameterChange::UnbondingPeriod(period) => self.unbonding_period().set(period),
+            ParameterChange::StakeTiers(tiers) => {
+                let mut stake_tiers = self.stake_tiers();
+                stake_tiers.clear();
+                for tier in tiers.iter() {
+                    stake_tiers.push(&tier);
+                }
+            }
+        }
+
+        self.parameter_change_executed_event(change_id, pending.change);
+    }
+
+    #[endpoint(cancelParameterChange)]
+    fn cancel_parameter_change(&self, change_id: u64) {
+        self.require_role(Role::Admin);
+        require!(
+            !self.pending_parameter_changes(change_id).is_empty(),
+            "Parameter change not found"
+        );
+
+        self.pending_parameter_changes(change_id).clear();
+        self.pending_parameter_change_ids().remove(&change_id);
+
+        self.parameter_change_cancelled_event(change_id, self.blockchain().get_caller());
+    }
+
+    fn validate_parameter_change(&self, change: &ParameterChange<Self::Api>) {
+        match change {
+            ParameterChange::DailyLimit(limit) => {
+                require!(
+                    *limit > 0 && *limit <= MAX_DAILY_LIMIT,
+                    "Daily limit out of range"
+                );
+            }
+            ParameterChange::MintingFee(fee) => {
+                require!(
+                    fee.to_u64().is_some_and(|fee| fee <= MAX_MINTING_FEE),
+                    "Minting fee above maximum"
+                );
+            }
+            ParameterChange::PlatformFee(fee_bps) => {
+                require!(
+                    *fee_bps <= MAX_PLATFORM_FEE_BPS,
+                    "Platform fee above maximum"
+                );
+            }
+            ParameterChange::TimelockDelay(delay) => {
+                require!(*delay <= MAX_TIMELOCK_DELAY, "Timelock delay above maximum");
+            }
+            ParameterChange::GenerationDeposit(deposit) => {
+                require!(
+                    deposit
+                        .to_u64()
+                        .is_some_and(|deposit| deposit <= MAX_GENERATION_DEPOSIT),
+                    "Generation deposit above maximum"
+                );
+            }
+            ParameterChange::UnbondingPeriod(period) => {
+                require!(
+                    *period <= MAX_UNBONDING_PERIOD,
+                    "Unbonding period above maximum"
+                );
+            }
+            ParameterChange::StakeTiers(tiers) => {
+                let mut previous: Option<StakeTier<Self::Api>> = None;
+                for tier in tiers.iter() {
+                    self.validate_parameter_change(&ParameterChange::DailyLimit(tier.daily_limit));
+                    if let Some(previous) = &previous {
+                        require!(
+                            tier.min_stake > previous.min_stake
+                                && tier.daily_limit > previous.daily_limit,
+                            "Tiers must increase in stake and limit"
+                        );
+                    }
+                    previous = Some(tier.clone());
+                }
+            }
+        }
+    }
+
+    // ========== FEE ACCOUNTING ==========
+
+    /// Replaces the fee recipients. Each entry is `address, share_bps, label`
+    /// and the shares must add up to 100%. With no recipients configured,
+    /// withdrawn fees go to the owner.
+    #[endpoint(setFeeRecipients)]
+    fn set_fee_recipients(
+        &self,
+        recipients: MultiValueEncoded<MultiValue3<ManagedAddress, u64, ManagedBuffer>>,
+    ) {
+        self.require_role(Role::Admin);
+        let mut fee_recipients = self.fee_recipients();
+        fee_recipients.clear();
+
+        let mut total_share = 0u64;
+        for recipient in recipients {
+            let (address, share_bps, label) = recipient.into_tuple();
+            require!(share_bps > 0, "Share must be greater than 0");
+            total_share += share_bps;
+            fee_recipients.push(&FeeRecipient {
+                address,
+                share_bps,
+                label,
+            });
+        }
+
+        require!(
+            fee_recipients.is_empty() || total_share == TOTAL_SHARE_BPS,
+            "Shares must add up to 100%"
+        );
+    }
+
+    /// Withdraws accrued fees of one source and token, split between the fee
+    /// recipients. Withdraws everything accrued unless `amount` is given.
+    /// Only accrued fees are sent, never funds held on behalf of users.
+    #[endpoint(withdrawFees)]
+    fn withdraw_fees(
+        &self,
+        source: FeeSource,
+        token: EgldOrEsdtTokenIdentifier,
+        amount: OptionalValue<BigUint>,
+    ) {
+        self.require_role(Role::Treasurer);
+        let accrued_mapper = self.accrued_fees(source, &token);
+        let accrued = accrued_mapper.get();
+        let amount = amount.into_option().unwrap_or_else(|| accrued.clone());
+
+        require!(amount > 0u64, "Nothing to withdraw");
+        require!(amount <= accrued, "Amount exceeds accrued fees");
+        accrued_mapper.set(&accrued - &amount);
+
+        let fee_recipients = self.fee_recipients();
+        if fee_recipients.is_empty() {
+            let owner = self.blockchain().get_owner_address();
+            self.send().direct(&owner, &token, 0, &amount);
+        } else {
+            // The last recipient also receives the rounding remainder
+            let mut remaining = amount.clone();
+            let recipient_count = fee_recipients.len();
+            for (index, recipient) in fee_recipients.iter().enumerate() {
+                let share = if index + 1 == recipient_count {
+                    remaining.clone()
+                } else {
+                    &amount * recipient.share_bps / TOTAL_SHARE_BPS
+                };
+                remaining -= &share;
+                self.send()
+                    .direct_non_zero(&recipient.address, &token, 0, &share);
+            }
+        }
+
+        self.fees_withdrawn_event(source, token, amount);
+    }
+
+    fn accrue_fees(&self, source: FeeSource, token: &EgldOrEsdtTokenIdentifier, amount: &BigUint) {
+        if amount > &BigUint::zero() {
+            self.accrued_fees(source, token)
+                .update(|fees| *fees += amount);
+        }
+    }
+
+    fn current_timestamp(&self) -> u64 {
+        self.blockchain()
+            .get_block_timestamp_seconds()
+            .as_u64_seconds()
+    }
+
+    // ========== VIEW FUNCTIONS ==========
+
+    #[view(getGeneration)]
+    fn get_generation(&self, generation_id: u64) -> Generation<Self::Api> {
+        self.generations(generation_id).get()
+    }
+
+    #[view(getListing)]
+    fn get_listing(&self, listing_id: u64) -> Listing<Self::Api> {
+        self.listings(listing_id).get()
+    }
+
+    #[view(getActiveListings)]
+    fn get_active_listings(
+        &self,
+        from: usize,
+        size: usize,
+    ) -> MultiValueEncoded<ListingView<Self::Api>> {
+        self.listing_views(self.active_listing_ids().iter(), from, size)
+    }
+
+    #[view(getListingsBySeller)]
+    fn get_listings_by_seller(
+        &self,
+        seller: ManagedAddress,
+        from: usize,
+        size: usize,
+    ) -> MultiValueEncoded<ListingView<Self::Api>> {
+        self.listing_views(self.seller_listing_ids(&seller).iter(), from, size)
+    }
+
+    #[view(getListingsByCategory)]
+    fn get_listings_by_category(
+        &self,
+        category: ManagedBuffer,
+        from: usize,
+        size: usize,
+    ) -> MultiValueEncoded<ListingView<Self::Api>> {
+        self.listing_views(self.category_listing_ids(&category).iter(), from, size)
+    }
+
+    /// Active listings priced in `payment_token` within `[min_price, max_price]`
+    #[view(getListingsByPriceRange)]
+    fn get_listings_by_price_range(
+        &self,
+        payment_token: EgldOrEsdtTokenIdentifier,
+        min_price: BigUint,
+        max_price: BigUint,
+        from: usize,
+        size: usize,
+    ) -> MultiValueEncoded<ListingView<Self::Api>> {
+        let active_listing_ids = self.active_listing_ids();
+        let matching_ids = active_listing_ids.iter().filter(|listing_id| {
+            let listing = self.listings(*listing_id).get();
+            listing.payment_token == payment_token
+                && listing.price >= min_price
+                && listing.price <= max_price
+        });
+        self.listing_views(matching_ids, from, size)
+    }
+
+    /// Listing ids come out of the indexes in insertion order, i.e. sorted by id
+    fn listing_views(
+        &self,
+        listing_ids: impl Iterator<Item = u64>,
+        from: usize,
+        size: usize,
+    ) -> MultiValueEncoded<ListingView<Self::Api>> {
+        let mut result = MultiValueEncoded::new();
+        for listing_id in listing_ids.skip(from).take(size) {
+            let listing = self.listings(listing_id).get();
+            let attributes: TemplateAttributes<Self::Api> = self
+                .held_template_data(listing.nft_nonce)
+                .decode_attributes();
+            let rating = self.template_ratings(listing.nft_nonce).get();
+            let average_rating = (rating.total_rating * 100)
+                .checked_div(rating.rating_count)
+                .unwrap_or_default();
+
+            result.push(ListingView {
+                category: self.generations(attributes.generation_id).get().category,
+                generation_id: attributes.generation_id,
+                average_rating,
+                rating_count: rating.rating_count,
+                uses: self.template_uses(listing.nft_nonce).get(),
+                listing,
+            });
+        }
+        result
+    }
+
+    #[view(getBundle)]
+    fn get_bundle(&self, bundle_id: u64) -> BundleListing<Self::Api> {
+        self.bundles(bundle_id).get()
+    }
+
+    #[view(getActiveBundles)]
+    fn get_active_bundles(
+        &self,
+        from: usize,
+        size: usize,
+    ) -> MultiValueEncoded<BundleListing<Self::Api>> {
+        let mut result = MultiValueEncoded::new();
+        for bundle_id in self.active_bundle_ids().iter().skip(from).take(size) {
+            result.push(self.bundles(bundle_id).get());
+        }
+        result
+    }
+
+    #[view(getRentalOffer)]
+    fn get_rental_offer(&self, nft_nonce: u64) -> RentalOffer<Self::Api> {
+        self.rental_offers(nft_nonce).get()
+    }
+
+    /// Whether `user` may use the template: either a running rental or
+    /// being the owner who escrowed it for rent.
+    #[view(hasAccess)]
+    fn has_access(&self, user: ManagedAddress, nft_nonce: u64) -> bool {
+        let rental_offer = self.rental_offers(nft_nonce);
+        if !rental_offer.is_empty() && rental_offer.get().owner == user {
+            return true;
+        }
+
+        self.template_access(&user, nft_nonce).get() > self.current_timestamp()
+    }
+
+    #[view(getAccessExpiry)]
+    fn get_access_expiry(&self, user: ManagedAddress, nft_nonce: u64) -> u64 {
+        self.template_access(&user, nft_nonce).get()
+    }
+
+    #[view(getAuction)]
+    fn get_auction(&self, auction_id: u64) -> Auction<Self::Api> {
+        self.auctions(auction_id).get()
+    }
+
+    #[view(getActiveAuctions)]
+    fn get_active_auctions(&self) -> MultiValueEncoded<Auction<Self::Api>> {
+        let mut result = MultiValueEncoded::new();
+        for auction_id in self.active_auctions().iter() {
+            result.push(self.auctions(auction_id).get());
+        }
+        result
+    }
+
+    #[view(getAuctionBids)]
+    fn get_auction_bids(&self, auction_id: u64) -> MultiValueEncoded<Bid<Self::Api>> {
+        self.auction_bids(auction_id).iter().collect()
+    }
+
+    #[view(getDutchAuction)]
+    fn get_dutch_auction(&self, auction_id: u64) -> DutchAuction<Self::Api> {
+        self.dutch_auctions(auction_id).get()
+    }
+
+    #[view(getDutchAuctionPrice)]
+    fn get_dutch_auction_price(&self, auction_id: u64) -> BigUint {
+        let auction = self.dutch_auctions(auction_id).get();
+        self.dutch_auction_price(&auction)
+    }
+
+    #[view(getOffer)]
+    fn get_offer(&self, offer_id: u64) -> Offer<Self::Api> {
+        self.offers(offer_id).get()
+    }
+
+    #[view(getOffersForTemplate)]
+    fn get_offers_for_template(&self, nft_nonce: u64) -> MultiValueEncoded<Offer<Self::Api>> {
+        let mut result = MultiValueEncoded::new();
+        for offer_id in self.nonce_offers(nft_nonce).iter() {
+            result.push(self.offers(offer_id).get());
+        }
+        result
+    }
+
+    #[view(getOffersByBuyer)]
+    fn get_offers_by_buyer(&self, buyer: ManagedAddress) -> MultiValueEncoded<Offer<Self::Api>> {
+        let mut result = MultiValueEncoded::new();
+        for offer_id in self.buyer_offers(&buyer).iter() {
+            result.push(self.offers(offer_id).get());
+        }
+        result
+    }
+
+    #[view(getUserGenerationsToday)]
+    fn get_user_generations_today(&self, user: ManagedAddress) -> u64 {
+        let current_day = self.current_timestamp() / 86400;
+        let last_day = self.user_last_generation_day(&user).get();
+
+        if current_day > last_day {
+            0 // New day, counter reset
+        } else {
+            self.user_generations_today(&user).get()
+        }
+    }
+
+    #[view(getUserGenerationCount)]
+    fn get_user_generation_count(&self, user: ManagedAddress) -> u64 {
+        self.user_generation_count(&user).get()
+    }
+
+    #[view(getTemplateRating)]
+    fn get_template_rating(&self, nft_nonce: u64) -> TemplateRatingInfo {
+        self.template_ratings(nft_nonce).get()
+    }
+
+    #[view(canRateTemplate)]
+    fn can_rate_template(&self, user: ManagedAddress, nft_nonce: u64) -> bool {
+        self.is_minted_template(nft_nonce)
+            && self.user_template_rating(&user, nft_nonce).is_empty()
+            && self.has_used_template(&user, nft_nonce)
+    }
+
+    #[view(getTemplateReviews)]
+    fn get_template_reviews(
+        &self,
+        nft_nonce: u64,
+        from: usize,
+        size: usize,
+    ) -> MultiValueEncoded<Review<Self::Api>> {
+        let mut result = MultiValueEncoded::new();
+        let reviewers = self.template_reviewers(nft_nonce);
+        let visible_raters = reviewers
+            .iter()
+            .filter(|rater| !self.review_hidden(rater, nft_nonce).get());
+        for rater in visible_raters.skip(from).take(size) {
+            result.push(self.template_reviews(&rater, nft_nonce).get());
+        }
+        result
+    }
+
+    #[view(getBayesianScore)]
+    fn get_bayesian_score(&self, nft_nonce: u64) -> u64 {
+        self.bayesian_score(&self.template_ratings(nft_nonce).get())
+    }
+
+    /// Best-ranked templates of a category, highest score first
+    #[view(getTopTemplates)]
+    fn get_top_templates(&self, category: ManagedBuffer) -> MultiValueEncoded<LeaderboardEntry> {
+        self.category_leaderboard(&category)
+            .get()
+            .into_iter()
+            .collect()
+    }
+
+    #[view(isReviewHidden)]
+    fn is_review_hidden(&self, rater: ManagedAddress, nft_nonce: u64) -> bool {
+        self.review_hidden(&rater, nft_nonce).get()
+    }
+
+    #[view(isTemplateFlagged)]
+    fn get_is_template_flagged(&self, nft_nonce: u64) -> bool {
+        self.is_template_flagged(nft_nonce)
+    }
+
+    #[view(getFlaggedTemplates)]
+    fn get_flagged_templates(&self) -> MultiValueEncoded<MultiValue2<u64, ManagedBuffer>> {
+        let mut result = MultiValueEncoded::new();
+        for nft_nonce in self.flagged_templates().iter() {
+            result.push((nft_nonce, self.template_flag_reason(nft_nonce).get()).into());
+        }
+        result
+    }
+
+    #[view(getReport)]
+    fn get_report(&self, report_id: u64) -> Report<Self::Api> {
+        self.reports(report_id).get()
+    }
+
+    #[view(getOpenReports)]
+    fn get_open_reports(&self, from: usize, size: usize) -> MultiValueEncoded<Report<Self::Api>> {
+        let mut result = MultiValueEncoded::new();
+        for report_id in self.open_report_ids().iter().skip(from).take(size) {
+            result.push(self.reports(report_id).get());
+        }
+        result
+    }
+
+    #[view(getReportStake)]
+    fn get_report_stake(&self) -> BigUint {
+        self.report_stake().get()
+    }
+
+    #[view(getCreatorProfile)]
+    fn get_creator_profile(&self, creator: ManagedAddress) -> CreatorProfile<Self::Api> {
+        let mut sales_volume = ManagedVec::new();
+        let mut royalties_earned = ManagedVec::new();
+        for token in self.creator_sale_tokens(&creator).iter() {
+            let volume = self.creator_sales_volume(&creator, &token).get();
+            sales_volume.push(EgldOrEsdtTokenPayment::new(token.clone(), 0, volume));
+            let royalties = self.creator_royalties_earned(&creator, &token).get();
+            if royalties > 0u64 {
+                royalties_earned.push(EgldOrEsdtTokenPayment::new(token, 0, royalties));
+            }
+        }
+
+        let rating = self.creator_ratings(&creator).get();
+        let average_rating = (rating.total_rating * 100)
+            .checked_div(rating.rating_count)
+            .unwrap_or_default();
+
+        CreatorProfile {
+            generation_count: self.user_generation_count(&creator).get(),
+            minted_templates: self.creator_minted_count(&creator).get(),
+            sales_count: self.creator_sales_count(&creator).get(),
+            sales_volume,
+            royalties_earned,
+            average_rating,
+            rating_count: rating.rating_count,
+            achievements: self.user_achievements(&creator).iter().collect(),
+            reputation: self.creator_reputation(&creator),
+            creator,
+        }
+    }
+
+    /// Highest-reputation creators, best first
+    #[view(getTopCreators)]
+    fn get_top_creators(&self) -> MultiValueEncoded<CreatorRankEntry<Self::Api>> {
+        self.top_creators().get().into_iter().collect()
+    }
+
+    #[view(getGenerationFeedback)]
+    fn get_generation_feedback(&self, generation_id: u64) -> OptionalValue<GenerationFeedback> {
+        let feedback = self.generation_feedback(generation_id);
+        if feedback.is_empty() {
+            OptionalValue::None
+        } else {
+            OptionalValue::Some(feedback.get())
+        }
+    }
+
+    #[view(getCategoryGenerationStats)]
+    fn get_category_generation_stats(&self, category: ManagedBuffer) -> GenerationQualityStats {
+        self.category_generation_stats(&category).get()
+    }
+
+    #[view(getGeneratorVersionStats)]
+    fn get_generator_version_stats(&self, version: ManagedBuffer) -> GenerationQualityStats {
+        self.generator_version_stats(&version).get()
+    }
+
+    #[view(getGeneratorVersion)]
+    fn get_generator_version(&self, generation_id: u64) -> ManagedBuffer {
+        self.generation_generator_version(generation_id).get()
+    }
+
+    #[view(getGeneratorVersions)]
+    fn get_generator_versions(&self) -> MultiValueEncoded<ManagedBuffer> {
+        self.generator_versions().iter().collect()
+    }
+
+    #[view(getUserReview)]
+    fn get_user_review(
+        &self,
+        user: ManagedAddress,
+        nft_nonce: u64,
+    ) -> OptionalValue<Review<Self::Api>> {
+        let review = self.template_reviews(&user, nft_nonce);
+        if review.is_empty() {
+            OptionalValue::None
+        } else {
+            OptionalValue::Some(review.get())
+        }
+    }
+
+    #[view(getTemplateUses)]
+    fn get_template_uses(&self, nft_nonce: u64) -> u64 {
+        self.template_uses(nft_nonce).get()
+    }
+
+    #[view(getDailyLimit)]
     fn get_daily_limit(&self) -> u64 {
         self.daily_generation_limit().get()
     }
 
-    #[view(getMintingFee)]
-    fn get_minting_fee(&self) -> BigUint {
-        self.nft_minting_fee().get()
+    #[view(getMintingFee)]
+    fn get_minting_fee(&self) -> BigUint {
+        self.nft_minting_fee().get()
+    }
+
+    #[view(getCreatorRoyaltiesEarned)]
+    fn get_creator_royalties_earned(
+        &self,
+        creator: ManagedAddress,
+        token: EgldOrEsdtTokenIdentifier,
+    ) -> BigUint {
+        self.creator_royalties_earned(&creator, &token).get()
+    }
+
+    #[view(getPendingProceeds)]
+    fn get_pending_proceeds(
+        &self,
+        address: ManagedAddress,
+    ) -> MultiValueEncoded<MultiValue2<EgldOrEsdtTokenIdentifier, BigUint>> {
+        let mut result = MultiValueEncoded::new();
+        for token in self.pending_proceeds_tokens(&address).iter() {
+            let amount = self.pending_proceeds(&address, &token).get();
+            result.push((token, amount).into());
+        }
+        result
+    }
+
+    #[view(isPullPaymentsEnabled)]
+    fn is_pull_payments_enabled(&self) -> bool {
+        self.pull_payments_enabled().get()
+    }
+
+    #[view(isPaused)]
+    fn is_paused(&self, subsystem: Subsystem) -> bool {
+        self.paused(subsystem).get()
+    }
+
+    #[view(getPendingParameterChanges)]
+    fn get_pending_parameter_changes(
+        &self,
+    ) -> MultiValueEncoded<PendingParameterChange<Self::Api>> {
+        let mut result = MultiValueEncoded::new();
+        for change_id in self.pending_parameter_change_ids().iter() {
+            result.push(self.pending_parameter_changes(change_id).get());
+        }
+        result
+    }
+
+    #[view(getStakeInfo)]
+    fn get_stake_info(&self, user: ManagedAddress) -> StakeInfo<Self::Api> {
+        StakeInfo {
+            staked: self.user_stake(&user).get(),
+            tier: self.stake_tier_index(&user) as u32,
+            daily_limit: self.effective_daily_limit(&user),
+            unbonding_amount: self.user_unbonding_amount(&user).get(),
+            unbonding_unlock_at: self.user_unbonding_unlock_at(&user).get(),
+        }
+    }
+
+    #[view(getStakeTiers)]
+    fn get_stake_tiers(&self) -> MultiValueEncoded<StakeTier<Self::Api>> {
+        self.stake_tiers().iter().collect()
+    }
+
+    #[view(getStakeToken)]
+    fn get_stake_token_view(&self) -> EgldOrEsdtTokenIdentifier {
+        self.get_stake_token()
+    }
+
+    #[view(getUnbondingPeriod)]
+    fn get_unbonding_period(&self) -> u64 {
+        self.unbonding_period().get()
+    }
+
+    /// Stake token held for users, unbonding amounts included
+    #[view(getTotalStaked)]
+    fn get_total_staked(&self) -> BigUint {
+        self.total_staked().get()
+    }
+
+    #[view(getGenerationDepositAmount)]
+    fn get_generation_deposit_amount(&self) -> BigUint {
+        self.generation_deposit_amount().get()
+    }
+
+    /// Deposit still held for a generation, 0 once refunded or slashed
+    #[view(getGenerationDeposit)]
+    fn get_generation_deposit(&self, generation_id: u64) -> BigUint {
+        self.generation_deposits(generation_id).get()
+    }
+
+    #[view(getStorageVersion)]
+    fn get_storage_version(&self) -> u32 {
+        self.storage_version().get()
+    }
+
+    #[view(getTimelockDelay)]
+    fn get_timelock_delay(&self) -> u64 {
+        self.timelock_delay().get()
+    }
+
+    #[view(isAddressAllowed)]
+    fn get_is_address_allowed(&self, address: ManagedAddress) -> bool {
+        self.is_address_allowed(&address)
+    }
+
+    #[view(isAddressBlocked)]
+    fn is_address_blocked(&self, address: ManagedAddress) -> bool {
+        self.blocked_addresses().contains(&address)
+    }
+
+    #[view(getBlockedAddresses)]
+    fn get_blocked_addresses(&self) -> MultiValueEncoded<ManagedAddress> {
+        self.blocked_addresses().iter().collect()
+    }
+
+    #[view(isAllowlistOnly)]
+    fn is_allowlist_only(&self) -> bool {
+        self.allowlist_only().get()
+    }
+
+    #[view(getAllowlist)]
+    fn get_allowlist(&self) -> MultiValueEncoded<ManagedAddress> {
+        self.allowlisted_addresses().iter().collect()
+    }
+
+    #[view(getRoleMembers)]
+    fn get_role_members(&self, role: Role) -> MultiValueEncoded<ManagedAddress> {
+        self.role_members(role).iter().collect()
+    }
+
+    #[view(hasRole)]
+    fn get_has_role(&self, role: Role, account: ManagedAddress) -> bool {
+        self.has_role(role, &account)
+    }
+
+    #[view(getAcceptedPaymentTokens)]
+    fn get_accepted_payment_tokens(&self) -> MultiValueEncoded<TokenIdentifier> {
+        self.accepted_payment_tokens().iter().collect()
+    }
+
+    #[view(getAccruedFees)]
+    fn get_accrued_fees(&self, source: FeeSource, token: EgldOrEsdtTokenIdentifier) -> BigUint {
+        self.accrued_fees(source, &token).get()
+    }
+
+    #[view(getFeeRecipients)]
+    fn get_fee_recipients(&self) -> MultiValueEncoded<FeeRecipient<Self::Api>> {
+        self.fee_recipients().iter().collect()
+    }
+
+    #[view(getPlatformFee)]
+    fn get_platform_fee(&self) -> u64 {
+        self.platform_fee_percent().get()
     }
 
     // ========== STORAGE ==========
@@ -429,6 +3130,190 @@ pub trait ContractGenerator {
     #[storage_mapper("platformFeePercent")]
     fn platform_fee_percent(&self) -> SingleValueMapper<u64>;
 
+    #[storage_mapper("creatorMintedCount")]
+    fn creator_minted_count(&self, creator: &ManagedAddress) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("creatorSalesCount")]
+    fn creator_sales_count(&self, creator: &ManagedAddress) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("creatorSalesVolume")]
+    fn creator_sales_volume(
+        &self,
+        creator: &ManagedAddress,
+        token: &EgldOrEsdtTokenIdentifier,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("creatorSaleTokens")]
+    fn creator_sale_tokens(
+        &self,
+        creator: &ManagedAddress,
+    ) -> UnorderedSetMapper<EgldOrEsdtTokenIdentifier>;
+
+    #[storage_mapper("creatorRatings")]
+    fn creator_ratings(&self, creator: &ManagedAddress) -> SingleValueMapper<TemplateRatingInfo>;
+
+    #[storage_mapper("userAchievements")]
+    fn user_achievements(&self, user: &ManagedAddress) -> UnorderedSetMapper<ManagedBuffer>;
+
+    #[storage_mapper("topCreators")]
+    fn top_creators(&self) -> SingleValueMapper<ManagedVec<CreatorRankEntry<Self::Api>>>;
+
+    #[storage_mapper("generationGeneratorVersion")]
+    fn generation_generator_version(&self, generation_id: u64) -> SingleValueMapper<ManagedBuffer>;
+
+    #[storage_mapper("generatorVersions")]
+    fn generator_versions(&self) -> UnorderedSetMapper<ManagedBuffer>;
+
+    #[storage_mapper("generationFeedback")]
+    fn generation_feedback(&self, generation_id: u64) -> SingleValueMapper<GenerationFeedback>;
+
+    #[storage_mapper("categoryGenerationStats")]
+    fn category_generation_stats(
+        &self,
+        category: &ManagedBuffer,
+    ) -> SingleValueMapper<GenerationQualityStats>;
+
+    #[storage_mapper("generatorVersionStats")]
+    fn generator_version_stats(
+        &self,
+        version: &ManagedBuffer,
+    ) -> SingleValueMapper<GenerationQualityStats>;
+
+    #[storage_mapper("globalRatingTotal")]
+    fn global_rating_total(&self) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("globalRatingCount")]
+    fn global_rating_count(&self) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("categoryLeaderboard")]
+    fn category_leaderboard(
+        &self,
+        category: &ManagedBuffer,
+    ) -> SingleValueMapper<ManagedVec<LeaderboardEntry>>;
+
+    #[storage_mapper("reviewHidden")]
+    fn review_hidden(&self, rater: &ManagedAddress, nft_nonce: u64) -> SingleValueMapper<bool>;
+
+    #[storage_mapper("flaggedTemplates")]
+    fn flagged_templates(&self) -> UnorderedSetMapper<u64>;
+
+    #[storage_mapper("templateFlagReason")]
+    fn template_flag_reason(&self, nft_nonce: u64) -> SingleValueMapper<ManagedBuffer>;
+
+    #[storage_mapper("reportStake")]
+    fn report_stake(&self) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("nextReportId")]
+    fn next_report_id(&self) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("reports")]
+    fn reports(&self, id: u64) -> SingleValueMapper<Report<Self::Api>>;
+
+    #[storage_mapper("openReportIds")]
+    fn open_report_ids(&self) -> SetMapper<u64>;
+
+    #[storage_mapper("paused")]
+    fn paused(&self, subsystem: Subsystem) -> SingleValueMapper<bool>;
+
+    #[storage_mapper("stakeToken")]
+    fn stake_token(&self) -> SingleValueMapper<EgldOrEsdtTokenIdentifier>;
+
+    #[storage_mapper("stakeTiers")]
+    fn stake_tiers(&self) -> VecMapper<StakeTier<Self::Api>>;
+
+    #[storage_mapper("unbondingPeriod")]
+    fn unbonding_period(&self) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("userStake")]
+    fn user_stake(&self, user: &ManagedAddress) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("totalStaked")]
+    fn total_staked(&self) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("userUnbondingAmount")]
+    fn user_unbonding_amount(&self, user: &ManagedAddress) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("userUnbondingUnlockAt")]
+    fn user_unbonding_unlock_at(&self, user: &ManagedAddress) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("generationDepositAmount")]
+    fn generation_deposit_amount(&self) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("generationDeposits")]
+    fn generation_deposits(&self, generation_id: u64) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("storageVersion")]
+    fn storage_version(&self) -> SingleValueMapper<u32>;
+
+    #[storage_mapper("migrationStep")]
+    fn migration_step(&self) -> SingleValueMapper<u8>;
+
+    #[storage_mapper("migrationCursor")]
+    fn migration_cursor(&self) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("timelockDelay")]
+    fn timelock_delay(&self) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("nextParameterChangeId")]
+    fn next_parameter_change_id(&self) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("pendingParameterChanges")]
+    fn pending_parameter_changes(
+        &self,
+        change_id: u64,
+    ) -> SingleValueMapper<PendingParameterChange<Self::Api>>;
+
+    #[storage_mapper("pendingParameterChangeIds")]
+    fn pending_parameter_change_ids(&self) -> SetMapper<u64>;
+
+    #[storage_mapper("blockedAddresses")]
+    fn blocked_addresses(&self) -> UnorderedSetMapper<ManagedAddress>;
+
+    #[storage_mapper("allowlistOnly")]
+    fn allowlist_only(&self) -> SingleValueMapper<bool>;
+
+    #[storage_mapper("allowlistedAddresses")]
+    fn allowlisted_addresses(&self) -> UnorderedSetMapper<ManagedAddress>;
+
+    #[storage_mapper("roleMembers")]
+    fn role_members(&self, role: Role) -> UnorderedSetMapper<ManagedAddress>;
+
+    #[storage_mapper("acceptedPaymentTokens")]
+    fn accepted_payment_tokens(&self) -> UnorderedSetMapper<TokenIdentifier>;
+
+    #[storage_mapper("accruedFees")]
+    fn accrued_fees(
+        &self,
+        source: FeeSource,
+        token: &EgldOrEsdtTokenIdentifier,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("feeRecipients")]
+    fn fee_recipients(&self) -> VecMapper<FeeRecipient<Self::Api>>;
+
+    #[storage_mapper("creatorRoyaltiesEarned")]
+    fn creator_royalties_earned(
+        &self,
+        creator: &ManagedAddress,
+        token: &EgldOrEsdtTokenIdentifier,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("pullPaymentsEnabled")]
+    fn pull_payments_enabled(&self) -> SingleValueMapper<bool>;
+
+    #[storage_mapper("pendingProceeds")]
+    fn pending_proceeds(
+        &self,
+        address: &ManagedAddress,
+        token: &EgldOrEsdtTokenIdentifier,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("pendingProceedsTokens")]
+    fn pending_proceeds_tokens(
+        &self,
+        address: &ManagedAddress,
+    ) -> UnorderedSetMapper<EgldOrEsdtTokenIdentifier>;
+
     #[storage_mapper("nextGenerationId")]
     fn next_generation_id(&self) -> SingleValueMapper<u64>;
 
@@ -441,69 +3326,438 @@ pub trait ContractGenerator {
     #[storage_mapper("listings")]
     fn listings(&self, id: u64) -> SingleValueMapper<Listing<Self::Api>>;
 
+    /// Listings as stored before storage version 1, only read by migrations
+    #[storage_mapper("listings")]
+    fn listings_v0(&self, id: u64) -> SingleValueMapper<ListingV0<Self::Api>>;
+
+    #[storage_mapper("nextBundleId")]
+    fn next_bundle_id(&self) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("bundles")]
+    fn bundles(&self, id: u64) -> SingleValueMapper<BundleListing<Self::Api>>;
+
+    #[storage_mapper("activeBundleIds")]
+    fn active_bundle_ids(&self) -> SetMapper<u64>;
+
+    #[storage_mapper("rentalOffers")]
+    fn rental_offers(&self, nft_nonce: u64) -> SingleValueMapper<RentalOffer<Self::Api>>;
+
+    #[storage_mapper("templateAccess")]
+    fn template_access(&self, user: &ManagedAddress, nft_nonce: u64) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("nextAuctionId")]
+    fn next_auction_id(&self) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("auctions")]
+    fn auctions(&self, id: u64) -> SingleValueMapper<Auction<Self::Api>>;
+
+    #[storage_mapper("activeAuctions")]
+    fn active_auctions(&self) -> UnorderedSetMapper<u64>;
+
+    #[storage_mapper("auctionBids")]
+    fn auction_bids(&self, auction_id: u64) -> VecMapper<Bid<Self::Api>>;
+
+    #[storage_mapper("nextDutchAuctionId")]
+    fn next_dutch_auction_id(&self) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("dutchAuctions")]
+    fn dutch_auctions(&self, id: u64) -> SingleValueMapper<DutchAuction<Self::Api>>;
+
+    #[storage_mapper("nextOfferId")]
+    fn next_offer_id(&self) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("offers")]
+    fn offers(&self, id: u64) -> SingleValueMapper<Offer<Self::Api>>;
+
+    #[storage_mapper("nonceOffers")]
+    fn nonce_offers(&self, nft_nonce: u64) -> UnorderedSetMapper<u64>;
+
+    #[storage_mapper("buyerOffers")]
+    fn buyer_offers(&self, buyer: &ManagedAddress) -> UnorderedSetMapper<u64>;
+
+    #[storage_mapper("activeListingIds")]
+    fn active_listing_ids(&self) -> SetMapper<u64>;
+
+    #[storage_mapper("sellerListingIds")]
+    fn seller_listing_ids(&self, seller: &ManagedAddress) -> SetMapper<u64>;
+
+    #[storage_mapper("categoryListingIds")]
+    fn category_listing_ids(&self, category: &ManagedBuffer) -> SetMapper<u64>;
+
     #[storage_mapper("userGenerationsToday")]
     fn user_generations_today(&self, user: &ManagedAddress) -> SingleValueMapper<u64>;
 
     #[storage_mapper("userLastGenerationDay")]
     fn user_last_generation_day(&self, user: &ManagedAddress) -> SingleValueMapper<u64>;
 
-    #[storage_mapper("userGenerationCount")]
-    fn user_generation_count(&self, user: &ManagedAddress) -> SingleValueMapper<u64>;
+    #[storage_mapper("userGenerationCount")]
+    fn user_generation_count(&self, user: &ManagedAddress) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("templateUses")]
+    fn template_uses(&self, nft_nonce: u64) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("templateReviews")]
+    fn template_reviews(
+        &self,
+        user: &ManagedAddress,
+        nft_nonce: u64,
+    ) -> SingleValueMapper<Review<Self::Api>>;
+
+    #[storage_mapper("templateReviewers")]
+    fn template_reviewers(&self, nft_nonce: u64) -> SetMapper<ManagedAddress>;
+
+    #[storage_mapper("templateGeneration")]
+    fn template_generation(&self, nft_nonce: u64) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("templateAcquired")]
+    fn template_acquired(&self, user: &ManagedAddress, nft_nonce: u64) -> SingleValueMapper<bool>;
+
+    #[storage_mapper("templateRatings")]
+    fn template_ratings(&self, nft_nonce: u64) -> SingleValueMapper<TemplateRatingInfo>;
+
+    #[storage_mapper("userTemplateRating")]
+    fn user_template_rating(&self, user: &ManagedAddress, nft_nonce: u64) -> SingleValueMapper<u8>;
+
+    // ========== EVENTS ==========
+
+    #[event("generationRequested")]
+    fn generation_requested_event(
+        &self,
+        #[indexed] generation_id: u64,
+        #[indexed] creator: ManagedAddress,
+        description_and_category: ManagedBuffer,
+    );
+
+    #[event("generationCompleted")]
+    fn generation_completed_event(
+        &self,
+        #[indexed] generation_id: u64,
+        #[indexed] creator: ManagedAddress,
+        #[indexed] success: bool,
+        code_hash: ManagedBuffer,
+    );
+
+    #[event("templateNftMinted")]
+    fn template_nft_minted_event(
+        &self,
+        #[indexed] generation_id: u64,
+        #[indexed] nft_nonce: u64,
+        #[indexed] creator: ManagedAddress,
+    );
+
+    #[event("templateListed")]
+    fn template_listed_event(
+        &self,
+        #[indexed] listing_id: u64,
+        #[indexed] nft_nonce: u64,
+        #[indexed] seller: ManagedAddress,
+        #[indexed] payment_token: EgldOrEsdtTokenIdentifier,
+        price: BigUint,
+    );
+
+    #[event("templatePurchased")]
+    fn template_purchased_event(
+        &self,
+        #[indexed] listing_id: u64,
+        #[indexed] buyer: ManagedAddress,
+        #[indexed] seller: ManagedAddress,
+        #[indexed] payment_token: EgldOrEsdtTokenIdentifier,
+        proceeds: SaleProceeds<Self::Api>,
+    );
+
+    #[event("listingCancelled")]
+    fn listing_cancelled_event(
+        &self,
+        #[indexed] listing_id: u64,
+        #[indexed] nft_nonce: u64,
+        #[indexed] moderator: ManagedAddress,
+    );
+
+    #[event("listingWithdrawn")]
+    fn listing_withdrawn_event(
+        &self,
+        #[indexed] listing_id: u64,
+        #[indexed] nft_nonce: u64,
+        #[indexed] seller: ManagedAddress,
+    );
+
+    #[event("listingExpired")]
+    fn listing_expired_event(
+        &self,
+        #[indexed] listing_id: u64,
+        #[indexed] nft_nonce: u64,
+        #[indexed] seller: ManagedAddress,
+    );
+
+    #[event("royaltyPaid")]
+    fn royalty_paid_event(
+        &self,
+        #[indexed] nft_nonce: u64,
+        #[indexed] creator: ManagedAddress,
+        #[indexed] payment_token: EgldOrEsdtTokenIdentifier,
+        amount: BigUint,
+    );
+
+    #[event("reviewHidden")]
+    fn review_hidden_event(
+        &self,
+        #[indexed] nft_nonce: u64,
+        #[indexed] rater: ManagedAddress,
+        hidden: bool,
+    );
+
+    #[event("templateFlagged")]
+    fn template_flagged_event(
+        &self,
+        #[indexed] nft_nonce: u64,
+        #[indexed] moderator: ManagedAddress,
+        reason_cid: ManagedBuffer,
+    );
+
+    #[event("templateUnflagged")]
+    fn template_unflagged_event(&self, #[indexed] nft_nonce: u64, moderator: ManagedAddress);
+
+    #[event("templateReported")]
+    fn template_reported_event(
+        &self,
+        #[indexed] report_id: u64,
+        #[indexed] nft_nonce: u64,
+        reporter: ManagedAddress,
+    );
+
+    #[event("reportResolved")]
+    fn report_resolved_event(
+        &self,
+        #[indexed] report_id: u64,
+        #[indexed] moderator: ManagedAddress,
+        upheld: bool,
+    );
+
+    #[event("staked")]
+    fn staked_event(&self, #[indexed] user: ManagedAddress, amount: BigUint);
+
+    #[event("unstaked")]
+    fn unstaked_event(
+        &self,
+        #[indexed] user: ManagedAddress,
+        #[indexed] unlock_at: u64,
+        amount: BigUint,
+    );
+
+    #[event("unbondedWithdrawn")]
+    fn unbonded_withdrawn_event(&self, #[indexed] user: ManagedAddress, amount: BigUint);
+
+    #[event("generationDepositRefunded")]
+    fn generation_deposit_refunded_event(
+        &self,
+        #[indexed] generation_id: u64,
+        #[indexed] creator: ManagedAddress,
+        amount: BigUint,
+    );
+
+    #[event("generationDepositHeld")]
+    fn generation_deposit_held_event(
+        &self,
+        #[indexed] generation_id: u64,
+        #[indexed] creator: ManagedAddress,
+        amount: BigUint,
+    );
+
+    #[event("generationDepositSlashed")]
+    fn generation_deposit_slashed_event(
+        &self,
+        #[indexed] generation_id: u64,
+        #[indexed] moderator: ManagedAddress,
+        amount: BigUint,
+    );
+
+    #[event("storageMigrated")]
+    fn storage_migrated_event(&self, #[indexed] from_version: u32, #[indexed] to_version: u32);
+
+    #[event("parameterChangeProposed")]
+    fn parameter_change_proposed_event(
+        &self,
+        #[indexed] change_id: u64,
+        #[indexed] executable_at: u64,
+        change: ParameterChange<Self::Api>,
+    );
+
+    #[event("parameterChangeExecuted")]
+    fn parameter_change_executed_event(
+        &self,
+        #[indexed] change_id: u64,
+        change: ParameterChange<Self::Api>,
+    );
+
+    #[event("parameterChangeCancelled")]
+    fn parameter_change_cancelled_event(
+        &self,
+        #[indexed] change_id: u64,
+        cancelled_by: ManagedAddress,
+    );
+
+    #[event("blocklistChanged")]
+    fn blocklist_changed_event(&self, #[indexed] address: ManagedAddress, blocked: bool);
+
+    #[event("allowlistChanged")]
+    fn allowlist_changed_event(&self, #[indexed] address: ManagedAddress, allowed: bool);
+
+    #[event("allowlistModeChanged")]
+    fn allowlist_mode_changed_event(&self, enabled: bool);
+
+    #[event("roleGranted")]
+    fn role_granted_event(
+        &self,
+        #[indexed] role: Role,
+        #[indexed] account: ManagedAddress,
+        granted_by: ManagedAddress,
+    );
+
+    #[event("roleRevoked")]
+    fn role_revoked_event(
+        &self,
+        #[indexed] role: Role,
+        #[indexed] account: ManagedAddress,
+        revoked_by: ManagedAddress,
+    );
+
+    #[event("pauseChanged")]
+    fn pause_changed_event(&self, #[indexed] subsystem: Subsystem, paused: bool);
 
-    #[storage_mapper("templateUses")]
-    fn template_uses(&self, nft_nonce: u64) -> SingleValueMapper<u64>;
+    #[event("feesWithdrawn")]
+    fn fees_withdrawn_event(
+        &self,
+        #[indexed] source: FeeSource,
+        #[indexed] token: EgldOrEsdtTokenIdentifier,
+        amount: BigUint,
+    );
 
-    #[storage_mapper("templateRatings")]
-    fn template_ratings(&self, nft_nonce: u64) -> SingleValueMapper<TemplateRatingInfo>;
+    #[event("proceedsClaimed")]
+    fn proceeds_claimed_event(
+        &self,
+        #[indexed] address: ManagedAddress,
+        #[indexed] token: EgldOrEsdtTokenIdentifier,
+        amount: BigUint,
+    );
 
-    #[storage_mapper("userTemplateRating")]
-    fn user_template_rating(&self, user: &ManagedAddress, nft_nonce: u64) -> SingleValueMapper<u8>;
+    #[event("bundleListed")]
+    fn bundle_listed_event(
+        &self,
+        #[indexed] bundle_id: u64,
+        #[indexed] seller: ManagedAddress,
+        price: BigUint,
+    );
 
-    // ========== EVENTS ==========
+    #[event("bundlePurchased")]
+    fn bundle_purchased_event(
+        &self,
+        #[indexed] bundle_id: u64,
+        #[indexed] buyer: ManagedAddress,
+        #[indexed] seller: ManagedAddress,
+        #[indexed] price: BigUint,
+        platform_fee: BigUint,
+    );
 
-    #[event("generationRequested")]
-    fn generation_requested_event(
+    #[event("templateListedForRent")]
+    fn template_listed_for_rent_event(
         &self,
-        #[indexed] generation_id: u64,
-        #[indexed] creator: ManagedAddress,
-        description_and_category: ManagedBuffer,
+        #[indexed] nft_nonce: u64,
+        #[indexed] owner: ManagedAddress,
+        price_per_day: BigUint,
     );
 
-    #[event("generationCompleted")]
-    fn generation_completed_event(
+    #[event("templateRented")]
+    fn template_rented_event(
         &self,
-        #[indexed] generation_id: u64,
-        #[indexed] creator: ManagedAddress,
-        #[indexed] success: bool,
-        code_hash: ManagedBuffer,
+        #[indexed] nft_nonce: u64,
+        #[indexed] renter: ManagedAddress,
+        #[indexed] access_until: u64,
+        price: BigUint,
     );
 
-    #[event("templateNftMinted")]
-    fn template_nft_minted_event(
+    #[event("auctionCreated")]
+    fn auction_created_event(
         &self,
-        #[indexed] generation_id: u64,
+        #[indexed] auction_id: u64,
         #[indexed] nft_nonce: u64,
-        #[indexed] creator: ManagedAddress,
+        #[indexed] seller: ManagedAddress,
+        #[indexed] end_time: u64,
+        starting_price: BigUint,
     );
 
-    #[event("templateListed")]
-    fn template_listed_event(
+    #[event("bidPlaced")]
+    fn bid_placed_event(
         &self,
-        #[indexed] listing_id: u64,
+        #[indexed] auction_id: u64,
+        #[indexed] bidder: ManagedAddress,
+        #[indexed] end_time: u64,
+        amount: BigUint,
+    );
+
+    #[event("auctionEnded")]
+    fn auction_ended_event(
+        &self,
+        #[indexed] auction_id: u64,
+        #[indexed] winner: ManagedAddress,
+        #[indexed] seller: ManagedAddress,
+        winning_bid: BigUint,
+    );
+
+    #[event("auctionCancelled")]
+    fn auction_cancelled_event(&self, #[indexed] auction_id: u64, seller: ManagedAddress);
+
+    #[event("dutchAuctionCreated")]
+    fn dutch_auction_created_event(
+        &self,
+        #[indexed] auction_id: u64,
         #[indexed] nft_nonce: u64,
         #[indexed] seller: ManagedAddress,
-        price: BigUint,
+        start_price: BigUint,
     );
 
-    #[event("templatePurchased")]
-    fn template_purchased_event(
+    #[event("dutchAuctionPurchased")]
+    fn dutch_auction_purchased_event(
         &self,
-        #[indexed] listing_id: u64,
+        #[indexed] auction_id: u64,
         #[indexed] buyer: ManagedAddress,
         #[indexed] seller: ManagedAddress,
         price: BigUint,
     );
 
+    #[event("offerMade")]
+    fn offer_made_event(
+        &self,
+        #[indexed] offer_id: u64,
+        #[indexed] nft_nonce: u64,
+        #[indexed] buyer: ManagedAddress,
+        amount: BigUint,
+    );
+
+    #[event("offerAccepted")]
+    fn offer_accepted_event(
+        &self,
+        #[indexed] offer_id: u64,
+        #[indexed] buyer: ManagedAddress,
+        #[indexed] seller: ManagedAddress,
+        amount: BigUint,
+    );
+
+    #[event("offerWithdrawn")]
+    fn offer_withdrawn_event(
+        &self,
+        #[indexed] offer_id: u64,
+        #[indexed] buyer: ManagedAddress,
+        amount: BigUint,
+    );
+
+    #[event("generationRated")]
+    fn generation_rated_event(
+        &self,
+        #[indexed] generation_id: u64,
+        #[indexed] creator: ManagedAddress,
+        quality: u8,
+    );
+
     #[event("templateRated")]
     fn template_rated_event(
         &self,
@@ -512,6 +3766,23 @@ pub trait ContractGenerator {
         rating: u8,
     );
 
+    #[event("ratingUpdated")]
+    fn rating_updated_event(
+        &self,
+        #[indexed] nft_nonce: u64,
+        #[indexed] rater: ManagedAddress,
+        #[indexed] old_rating: u8,
+        new_rating: u8,
+    );
+
+    #[event("ratingRemoved")]
+    fn rating_removed_event(
+        &self,
+        #[indexed] nft_nonce: u64,
+        #[indexed] rater: ManagedAddress,
+        old_rating: u8,
+    );
+
     #[event("achievementEarned")]
     fn achievement_earned_event(&self, #[indexed] user: ManagedAddress, achievement: ManagedBuffer);
 }
@@ -519,7 +3790,7 @@ pub trait ContractGenerator {
 // ========== STRUCTS & ENUMS ==========
 
 #[type_abi]
-#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Clone)]
+#[derive(TopEncode, NestedEncode, NestedDecode, PartialEq, Clone)]
 pub struct Generation<M: ManagedTypeApi> {
     pub id: u64,
     pub creator: ManagedAddress<M>,
@@ -540,7 +3811,7 @@ pub enum GenerationStatus {
 }
 
 #[type_abi]
-#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, Clone)]
+#[derive(TopEncode, NestedEncode, NestedDecode, Clone)]
 pub struct TemplateAttributes<M: ManagedTypeApi> {
     pub generation_id: u64,
     pub category: ManagedBuffer<M>,
@@ -552,18 +3823,421 @@ pub struct TemplateAttributes<M: ManagedTypeApi> {
 }
 
 #[type_abi]
-#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, Clone)]
+#[derive(TopEncode, NestedEncode, NestedDecode, Clone)]
 pub struct Listing<M: ManagedTypeApi> {
     pub id: u64,
     pub seller: ManagedAddress<M>,
     pub nft_nonce: u64,
+    pub payment_token: EgldOrEsdtTokenIdentifier<M>,
+    pub price: BigUint<M>,
+    pub expires_at: u64, // 0 means the listing never expires
+}
+
+/// Listing layout of storage version 0
+#[derive(TopEncode, TopDecode)]
+pub struct ListingV0<M: ManagedTypeApi> {
+    pub id: u64,
+    pub seller: ManagedAddress<M>,
+    pub nft_nonce: u64,
+    pub price: BigUint<M>,
+    pub active: bool,
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, Clone)]
+pub struct BundleListing<M: ManagedTypeApi> {
+    pub id: u64,
+    pub seller: ManagedAddress<M>,
+    pub nft_nonces: ManagedVec<M, u64>,
+    pub payment_token: EgldOrEsdtTokenIdentifier<M>,
+    pub price: BigUint<M>,
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, Clone)]
+pub struct RentalOffer<M: ManagedTypeApi> {
+    pub nft_nonce: u64,
+    pub owner: ManagedAddress<M>,
+    pub payment_token: EgldOrEsdtTokenIdentifier<M>,
+    pub price_per_day: BigUint<M>,
+    pub max_days: u64,
+    pub rented_until: u64, // end of the longest running rental
+    pub available: bool,
+}
+
+/// A listing together with the metadata of the template being sold
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, Clone)]
+pub struct ListingView<M: ManagedTypeApi> {
+    pub listing: Listing<M>,
+    pub generation_id: u64,
+    pub category: ManagedBuffer<M>,
+    pub average_rating: u64, // scaled by 100, e.g. 450 = 4.50 stars
+    pub rating_count: u64,
+    pub uses: u64,
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, ManagedVecItem, Clone)]
+pub struct LeaderboardEntry {
+    pub nft_nonce: u64,
+    pub score: u64, // bayesian rating, scaled by 100
+    pub uses: u64,
+}
+
+impl LeaderboardEntry {
+    /// Higher score first, more uses breaks ties
+    fn ranks_above(&self, other: &LeaderboardEntry) -> bool {
+        (self.score, self.uses) > (other.score, other.uses)
+    }
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, ManagedVecItem, Clone)]
+pub struct CreatorRankEntry<M: ManagedTypeApi> {
+    pub creator: ManagedAddress<M>,
+    pub reputation: u64,
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode)]
+pub struct CreatorProfile<M: ManagedTypeApi> {
+    pub creator: ManagedAddress<M>,
+    pub generation_count: u64,
+    pub minted_templates: u64,
+    pub sales_count: u64, // sales and rentals of the creator's templates
+    pub sales_volume: ManagedVec<M, EgldOrEsdtTokenPayment<M>>,
+    pub royalties_earned: ManagedVec<M, EgldOrEsdtTokenPayment<M>>,
+    pub average_rating: u64, // scaled by 100
+    pub rating_count: u64,
+    pub achievements: ManagedVec<M, ManagedBuffer<M>>,
+    pub reputation: u64,
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, Clone)]
+pub struct Report<M: ManagedTypeApi> {
+    pub id: u64,
+    pub reporter: ManagedAddress<M>,
+    pub nft_nonce: u64,
+    pub reason_cid: ManagedBuffer<M>,
+    pub stake: BigUint<M>,
+    pub timestamp: u64,
+    pub status: ReportStatus,
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Clone)]
+pub enum ReportStatus {
+    Open,
+    Upheld,
+    Dismissed,
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, ManagedVecItem, Clone)]
+pub struct StakeTier<M: ManagedTypeApi> {
+    pub min_stake: BigUint<M>,
+    pub daily_limit: u64,
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode)]
+pub struct StakeInfo<M: ManagedTypeApi> {
+    pub staked: BigUint<M>,
+    pub tier: u32, // 0 means below the first tier
+    pub daily_limit: u64,
+    pub unbonding_amount: BigUint<M>,
+    pub unbonding_unlock_at: u64,
+}
+
+/// Configuration changes that go through the timelock
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, Clone)]
+pub enum ParameterChange<M: ManagedTypeApi> {
+    DailyLimit(u64),
+    MintingFee(BigUint<M>),
+    PlatformFee(u64),                        // basis points
+    TimelockDelay(u64),                      // seconds
+    GenerationDeposit(BigUint<M>),           // 0 disables the deposit
+    UnbondingPeriod(u64),                    // seconds
+    StakeTiers(ManagedVec<M, StakeTier<M>>), // ordered by increasing stake and limit
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, Clone)]
+pub struct PendingParameterChange<M: ManagedTypeApi> {
+    pub id: u64,
+    pub change: ParameterChange<M>,
+    pub proposed_by: ManagedAddress<M>,
+    pub executable_at: u64,
+}
+
+/// Privileged roles. The owner holds all of them implicitly.
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Clone, Copy)]
+pub enum Role {
+    Admin,     // configuration and parameters
+    Oracle,    // completes generations
+    Moderator, // reviews, flags, reports and listing removal
+    Treasurer, // fee withdrawals
+    Guardian,  // emergency pause
+}
+
+/// Parts of the contract that can be paused independently
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Clone, Copy)]
+pub enum Subsystem {
+    Generation,
+    Minting,
+    Marketplace,
+    Ratings,
+}
+
+/// Where accrued platform fees came from
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Clone, Copy)]
+pub enum FeeSource {
+    Minting,
+    Marketplace,
+    Slashed, // forfeited stakes and deposits
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, Clone)]
+pub struct FeeRecipient<M: ManagedTypeApi> {
+    pub address: ManagedAddress<M>,
+    pub share_bps: u64,
+    pub label: ManagedBuffer<M>, // e.g. "treasury", "dev fund", "oracle pool"
+}
+
+/// How the price of a sale was split between the platform, the creator and the seller
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, Clone)]
+pub struct SaleProceeds<M: ManagedTypeApi> {
     pub price: BigUint<M>,
+    pub platform_fee: BigUint<M>,
+    pub creator: ManagedAddress<M>,
+    pub royalty: BigUint<M>,
+    pub seller_amount: BigUint<M>,
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, Clone)]
+pub struct Auction<M: ManagedTypeApi> {
+    pub id: u64,
+    pub seller: ManagedAddress<M>,
+    pub nft_nonce: u64,
+    pub payment_token: EgldOrEsdtTokenIdentifier<M>,
+    pub starting_price: BigUint<M>,
+    pub min_bid_increment: BigUint<M>,
+    pub end_time: u64,
+    pub highest_bid: BigUint<M>,
+    pub highest_bidder: ManagedAddress<M>, // zero address until the first bid
+    pub active: bool,
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, Clone)]
+pub struct DutchAuction<M: ManagedTypeApi> {
+    pub id: u64,
+    pub seller: ManagedAddress<M>,
+    pub nft_nonce: u64,
+    pub payment_token: EgldOrEsdtTokenIdentifier<M>,
+    pub start_price: BigUint<M>,
+    pub floor_price: BigUint<M>,
+    pub start_time: u64,
+    pub end_time: u64,
     pub active: bool,
 }
 
 #[type_abi]
-#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, Clone, Default)]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, Clone)]
+pub struct Offer<M: ManagedTypeApi> {
+    pub id: u64,
+    pub buyer: ManagedAddress<M>,
+    pub nft_nonce: u64,
+    pub payment_token: EgldOrEsdtTokenIdentifier<M>,
+    pub amount: BigUint<M>,
+    pub expires_at: u64,
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, Clone)]
+pub struct Bid<M: ManagedTypeApi> {
+    pub bidder: ManagedAddress<M>,
+    pub amount: BigUint<M>,
+    pub timestamp: u64,
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, Clone)]
+pub struct Review<M: ManagedTypeApi> {
+    pub rater: ManagedAddress<M>,
+    pub rating: u8,
+    pub review_cid: ManagedBuffer<M>, // IPFS CID of the written review, empty if none
+    pub timestamp: u64,
+}
+
+#[type_abi]
+#[derive(TopEncodeOrDefault, TopDecodeOrDefault, NestedEncode, NestedDecode, Clone, Default)]
 pub struct TemplateRatingInfo {
     pub total_rating: u64,
     pub rating_count: u64,
 }
+
+// Templates nobody rated yet have nothing in storage
+impl codec::EncodeDefault for TemplateRatingInfo {
+    fn is_default(&self) -> bool {
+        self.rating_count == 0
+    }
+}
+
+impl codec::DecodeDefault for TemplateRatingInfo {
+    fn default() -> Self {
+        Default::default()
+    }
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, Clone)]
+pub struct GenerationFeedback {
+    pub quality: u8,
+    pub compiled_as_is: bool,
+    pub needed_edits: bool,
+    pub timestamp: u64,
+}
+
+#[type_abi]
+#[derive(TopEncodeOrDefault, TopDecodeOrDefault, NestedEncode, NestedDecode, Clone, Default)]
+pub struct GenerationQualityStats {
+    pub rating_count: u64,
+    pub total_quality: u64,
+    pub compiled_as_is_count: u64,
+    pub needed_edits_count: u64,
+}
+
+impl GenerationQualityStats {
+    fn record(&mut self, feedback: &GenerationFeedback) {
+        self.rating_count += 1;
+        self.total_quality += feedback.quality as u64;
+        if feedback.compiled_as_is {
+            self.compiled_as_is_count += 1;
+        }
+        if feedback.needed_edits {
+            self.needed_edits_count += 1;
+        }
+    }
+}
+
+impl codec::EncodeDefault for GenerationQualityStats {
+    fn is_default(&self) -> bool {
+        self.rating_count == 0
+    }
+}
+
+impl codec::DecodeDefault for GenerationQualityStats {
+    fn default() -> Self {
+        Default::default()
+    }
+}
+
+// ========== STORAGE DECODING ==========
+//
+// `Generation`, `Listing` and `TemplateAttributes` are stored (the latter on
+// the NFT itself, where it can never be migrated) and only ever grow at the
+// end. Their top-level decoders are written out so that a field appended
+// later is read with `dep_decode_appended`, and records written before the
+// field existed decode with its default instead of failing. Nested decoding
+// stays strict.
+
+/// Decodes a field appended to a stored struct, or its default when the
+/// record was written before the field existed.
+pub fn dep_decode_appended<T, I, H>(input: &mut I, h: H) -> Result<T, H::HandledErr>
+where
+    T: NestedDecode + Default,
+    I: codec::NestedDecodeInput,
+    H: codec::DecodeErrorHandler,
+{
+    if input.is_depleted() {
+        Ok(T::default())
+    } else {
+        T::dep_decode_or_handle_err(input, h)
+    }
+}
+
+/// Rejects leftover bytes once every known field has been decoded
+pub fn top_decode_end<I, H>(input: &I, h: H) -> Result<(), H::HandledErr>
+where
+    I: codec::NestedDecodeInput,
+    H: codec::DecodeErrorHandler,
+{
+    if input.is_depleted() {
+        Ok(())
+    } else {
+        Err(h.handle_error(codec::DecodeError::INPUT_TOO_LONG))
+    }
+}
+
+impl<M: ManagedTypeApi> TopDecode for Generation<M> {
+    fn top_decode_or_handle_err<I, H>(input: I, h: H) -> Result<Self, H::HandledErr>
+    where
+        I: codec::TopDecodeInput,
+        H: codec::DecodeErrorHandler,
+    {
+        let mut input = input.into_nested_buffer();
+        let generation = Generation {
+            id: u64::dep_decode_or_handle_err(&mut input, h)?,
+            creator: ManagedAddress::dep_decode_or_handle_err(&mut input, h)?,
+            description: ManagedBuffer::dep_decode_or_handle_err(&mut input, h)?,
+            category: ManagedBuffer::dep_decode_or_handle_err(&mut input, h)?,
+            timestamp: u64::dep_decode_or_handle_err(&mut input, h)?,
+            status: GenerationStatus::dep_decode_or_handle_err(&mut input, h)?,
+            code_hash: ManagedBuffer::dep_decode_or_handle_err(&mut input, h)?,
+            nft_nonce: u64::dep_decode_or_handle_err(&mut input, h)?,
+        };
+        top_decode_end(&input, h)?;
+        Ok(generation)
+    }
+}
+
+impl<M: ManagedTypeApi> TopDecode for Listing<M> {
+    fn top_decode_or_handle_err<I, H>(input: I, h: H) -> Result<Self, H::HandledErr>
+    where
+        I: codec::TopDecodeInput,
+        H: codec::DecodeErrorHandler,
+    {
+        let mut input = input.into_nested_buffer();
+        let listing = Listing {
+            id: u64::dep_decode_or_handle_err(&mut input, h)?,
+            seller: ManagedAddress::dep_decode_or_handle_err(&mut input, h)?,
+            nft_nonce: u64::dep_decode_or_handle_err(&mut input, h)?,
+            payment_token: EgldOrEsdtTokenIdentifier::dep_decode_or_handle_err(&mut input, h)?,
+            price: BigUint::dep_decode_or_handle_err(&mut input, h)?,
+            expires_at: u64::dep_decode_or_handle_err(&mut input, h)?,
+        };
+        top_decode_end(&input, h)?;
+        Ok(listing)
+    }
+}
+
+impl<M: ManagedTypeApi> TopDecode for TemplateAttributes<M> {
+    fn top_decode_or_handle_err<I, H>(input: I, h: H) -> Result<Self, H::HandledErr>
+    where
+        I: codec::TopDecodeInput,
+        H: codec::DecodeErrorHandler,
+    {
+        let mut input = input.into_nested_buffer();
+        let attributes = TemplateAttributes {
+            generation_id: u64::dep_decode_or_handle_err(&mut input, h)?,
+            category: ManagedBuffer::dep_decode_or_handle_err(&mut input, h)?,
+            code_hash: ManagedBuffer::dep_decode_or_handle_err(&mut input, h)?,
+            creation_date: u64::dep_decode_or_handle_err(&mut input, h)?,
+            uses: u64::dep_decode_or_handle_err(&mut input, h)?,
+            total_rating: u64::dep_decode_or_handle_err(&mut input, h)?,
+            rating_count: u64::dep_decode_or_handle_err(&mut input, h)?,
+        };
+        top_decode_end(&input, h)?;
+        Ok(attributes)
+    }
+}
@@ -0,0 +1,503 @@
+mod common;
+
+use common::*;
+use contract::*;
+use multiversx_sc_scenario::imports::*;
+
+#[test]
+fn pause_flow() {
+    let mut world = setup();
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .payment(TestEsdtTransfer(NFT, 1, 1))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.list_template(
+                1,
+                BigUint::from(1000u64),
+                OptionalValue::None,
+                OptionalValue::None,
+            );
+        });
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.grant_role(Role::Guardian, BUYER.to_managed_address())
+        });
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .returns(ExpectError(4, "Only owner or guardian"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.pause(Subsystem::Marketplace)
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.pause(Subsystem::Marketplace)
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .egld(1000)
+        .returns(ExpectError(4, "Marketplace is paused"))
+        .whitebox(contract::contract_obj, |sc| sc.purchase_template(0));
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .egld(500)
+        .returns(ExpectError(4, "Marketplace is paused"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.make_offer(1, 5000);
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .returns(ExpectError(4, "Ratings are paused"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.pause(Subsystem::Ratings);
+            sc.rate_template(1, 5, OptionalValue::None);
+        });
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.unpause(Subsystem::Marketplace)
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .egld(1000)
+        .whitebox(contract::contract_obj, |sc| sc.purchase_template(0));
+}
+
+#[test]
+fn pause_still_returns_escrowed_funds() {
+    let mut world = setup();
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .payment(TestEsdtTransfer(NFT, 1, 1))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.create_auction(
+                1,
+                BigUint::from(1000u64),
+                BigUint::from(100u64),
+                5000,
+                OptionalValue::None,
+            );
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .egld(1000)
+        .whitebox(contract::contract_obj, |sc| sc.place_bid(0));
+    let nonce = mint(&mut world);
+    world
+        .tx()
+        .from(CREATOR)
+        .to(SC)
+        .payment(TestEsdtTransfer(NFT, nonce, 1))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.list_template(
+                nonce,
+                BigUint::from(1000u64),
+                OptionalValue::None,
+                OptionalValue::None,
+            );
+        });
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.timelock_delay().set(0);
+            let id = sc.propose_parameter_change(ParameterChange::GenerationDeposit(
+                BigUint::from(1000u64),
+            ));
+            sc.execute_parameter_change(id);
+        });
+    let mut gen_id = 0;
+    world
+        .tx()
+        .from(CREATOR)
+        .to(SC)
+        .egld(1000)
+        .whitebox(contract::contract_obj, |sc| {
+            gen_id = sc.generate_contract(ManagedBuffer::from("x"), ManagedBuffer::from("DeFi"));
+        });
+    world
+        .tx()
+        .from(CREATOR)
+        .to(SC)
+        .returns(ExpectError(4, "Generation is not paused"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.reclaim_generation_deposit(gen_id)
+        });
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.pause(Subsystem::Marketplace);
+            sc.pause(Subsystem::Generation);
+        });
+
+    // A won auction is not undone by the pause, it settles once unpaused
+    world.current_block().block_timestamp_seconds(6000);
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .returns(ExpectError(4, "Marketplace is paused"))
+        .whitebox(contract::contract_obj, |sc| sc.end_auction(0));
+
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .returns(ExpectError(4, "Only the seller can withdraw"))
+        .whitebox(contract::contract_obj, |sc| sc.withdraw_listing(0));
+    world
+        .tx()
+        .from(CREATOR)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.withdraw_listing(0);
+            assert_eq!(sc.get_active_listings(0, 10).len(), 0);
+        });
+    assert_holds_nft(&mut world, CREATOR, nonce);
+
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .returns(ExpectError(4, "Only the creator can reclaim the deposit"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.reclaim_generation_deposit(gen_id)
+        });
+    world
+        .tx()
+        .from(CREATOR)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.reclaim_generation_deposit(gen_id);
+            assert_eq!(sc.get_generation_deposit(gen_id), BigUint::zero());
+        });
+
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.unpause(Subsystem::Marketplace)
+        });
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| sc.end_auction(0));
+    assert_holds_nft(&mut world, BUYER, 1);
+}
+
+#[test]
+fn role_flow() {
+    let mut world = setup();
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .returns(ExpectError(4, "Only owner or admin"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.set_pull_payments_enabled(true)
+        });
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.grant_role(Role::Admin, SELLER.to_managed_address())
+        });
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.set_pull_payments_enabled(true);
+            sc.grant_role(Role::Oracle, BUYER.to_managed_address());
+            assert!(sc.get_has_role(Role::Oracle, BUYER.to_managed_address()));
+            assert_eq!(sc.get_role_members(Role::Oracle).len(), 1);
+        });
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .returns(ExpectError(4, "Only owner can manage admins"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.grant_role(Role::Admin, BUYER.to_managed_address())
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .returns(ExpectError(4, "Only owner or treasurer"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.withdraw_fees(
+                FeeSource::Minting,
+                EgldOrEsdtTokenIdentifier::egld(),
+                OptionalValue::None,
+            )
+        });
+    world
+        .tx()
+        .from(CREATOR)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.generate_contract(ManagedBuffer::from("vault"), ManagedBuffer::from("DeFi"));
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.complete_generation(
+                1,
+                ManagedBuffer::from("Qm2"),
+                true,
+                false,
+                OptionalValue::None,
+            );
+            sc.renounce_role(Role::Oracle);
+            assert!(!sc.get_has_role(Role::Oracle, BUYER.to_managed_address()));
+        });
+}
+
+#[test]
+fn timelock_flow() {
+    let mut world = setup();
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            let id = sc.propose_parameter_change(ParameterChange::DailyLimit(7));
+            assert_eq!(id, 0);
+            sc.propose_parameter_change(ParameterChange::PlatformFee(500));
+            assert_eq!(sc.get_pending_parameter_changes().len(), 2);
+        });
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .returns(ExpectError(4, "Timelock has not expired"))
+        .whitebox(contract::contract_obj, |sc| sc.execute_parameter_change(0));
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .returns(ExpectError(4, "Platform fee above maximum"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.propose_parameter_change(ParameterChange::PlatformFee(5000));
+        });
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .returns(ExpectError(4, "Timelock delay above maximum"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.propose_parameter_change(ParameterChange::TimelockDelay(u64::MAX));
+        });
+    world
+        .current_block()
+        .block_timestamp_seconds(1000 + 2 * 86400);
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.execute_parameter_change(0);
+            assert_eq!(sc.get_daily_limit(), 7);
+            sc.cancel_parameter_change(1);
+            assert_eq!(sc.get_pending_parameter_changes().len(), 0);
+            assert_eq!(sc.get_platform_fee(), 250);
+        });
+}
+
+#[test]
+fn upgrade_migration_flow() {
+    let mut world = setup();
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            // simulate a version 0 deployment
+            sc.storage_version().clear();
+            sc.template_generation(1).clear();
+            sc.creator_minted_count(&CREATOR.to_managed_address())
+                .clear();
+            sc.template_ratings(1).set(TemplateRatingInfo {
+                total_rating: 9,
+                rating_count: 2,
+            });
+        });
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.upgrade();
+            assert_eq!(sc.get_storage_version(), 0);
+            assert!(sc.is_paused(Subsystem::Marketplace));
+            assert!(!sc.migrate_storage(1));
+            assert!(sc.migrate_storage(1));
+            assert_eq!(sc.get_storage_version(), 1);
+            assert_eq!(
+                sc.creator_minted_count(&CREATOR.to_managed_address()).get(),
+                1
+            );
+            assert_eq!(sc.global_rating_count().get(), 2);
+            assert_eq!(sc.get_top_templates(ManagedBuffer::from("DeFi")).len(), 1);
+        });
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .returns(ExpectError(4, "Storage is up to date"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.migrate_storage(10);
+        });
+    world
+        .tx()
+        .from(OWNER)
+        .raw_deploy()
+        .code(CODE)
+        .new_address(TestSCAddress::new("gen2"))
+        .returns(ExpectError(4, "Daily limit out of range"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.init(0, BigUint::zero(), 250);
+        });
+}
+
+#[test]
+fn v0_listing_migration_flow() {
+    let mut world = setup();
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .payment(TestEsdtTransfer(NFT, 1, 1))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.list_template(
+                1,
+                BigUint::from(1000u64),
+                OptionalValue::None,
+                OptionalValue::None,
+            );
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.storage_version().clear();
+            sc.template_generation(1).clear();
+            sc.active_listing_ids().clear();
+            sc.category_listing_ids(&ManagedBuffer::from("DeFi"))
+                .clear();
+            sc.next_listing_id().set(2);
+            sc.listings_v0(0).set(ListingV0 {
+                id: 0,
+                seller: SELLER.to_managed_address(),
+                nft_nonce: 1,
+                price: BigUint::from(700u64),
+                active: true,
+            });
+            sc.listings_v0(1).set(ListingV0 {
+                id: 1,
+                seller: SELLER.to_managed_address(),
+                nft_nonce: 1,
+                price: BigUint::from(500u64),
+                active: false,
+            });
+        });
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.upgrade();
+            assert!(sc.migrate_storage(10));
+            let listing = sc.listings(0).get();
+            assert_eq!(listing.price, BigUint::from(700u64));
+            assert_eq!(listing.expires_at, 0);
+            assert!(sc.listings(1).is_empty());
+            assert_eq!(sc.get_active_listings(0, 10).len(), 1);
+            assert_eq!(
+                sc.get_listings_by_category(ManagedBuffer::from("DeFi"), 0, 10)
+                    .len(),
+                1
+            );
+        });
+}
+
+#[test]
+fn blocklist_flow() {
+    let mut world = setup();
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            let mut addresses = MultiValueEncoded::new();
+            addresses.push(SELLER.to_managed_address());
+            sc.block_addresses(addresses);
+            assert!(!sc.get_is_address_allowed(SELLER.to_managed_address()));
+        });
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .returns(ExpectError(4, "Address is blocked"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.generate_contract(ManagedBuffer::from("x"), ManagedBuffer::from("DeFi"));
+        });
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.set_allowlist_only(true);
+            let mut addresses = MultiValueEncoded::new();
+            addresses.push(BUYER.to_managed_address());
+            sc.add_to_allowlist(addresses);
+        });
+    world
+        .tx()
+        .from(CREATOR)
+        .to(SC)
+        .returns(ExpectError(4, "Address is not allowlisted"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.generate_contract(ManagedBuffer::from("x"), ManagedBuffer::from("DeFi"));
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.generate_contract(ManagedBuffer::from("x"), ManagedBuffer::from("DeFi"));
+        });
+}
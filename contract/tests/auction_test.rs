@@ -0,0 +1,155 @@
+mod common;
+
+use common::*;
+use contract::*;
+use multiversx_sc_scenario::imports::*;
+
+#[test]
+fn english_auction_flow() {
+    let mut world = setup();
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .payment(TestEsdtTransfer(NFT, 1, 1))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.create_auction(
+                1,
+                BigUint::from(1000u64),
+                BigUint::from(100u64),
+                5000,
+                OptionalValue::None,
+            );
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .egld(900)
+        .returns(ExpectError(4, "Bid too low"))
+        .whitebox(contract::contract_obj, |sc| sc.place_bid(0));
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .egld(1000)
+        .whitebox(contract::contract_obj, |sc| sc.place_bid(0));
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .egld(1050)
+        .returns(ExpectError(4, "Bid too low"))
+        .whitebox(contract::contract_obj, |sc| sc.place_bid(0));
+    world.current_block().block_timestamp_seconds(4900);
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .egld(1100)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.place_bid(0);
+            assert_eq!(sc.get_auction(0).end_time, 5500);
+            assert_eq!(sc.get_auction_bids(0).len(), 2);
+        });
+    world.check_account(BUYER).balance(1_000_000u64);
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .returns(ExpectError(4, "Auction has not ended yet"))
+        .whitebox(contract::contract_obj, |sc| sc.end_auction(0));
+    world.current_block().block_timestamp_seconds(5500);
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| sc.end_auction(0));
+    assert_holds_nft(&mut world, OWNER, 1);
+    world.check_account(SELLER).balance(1_000_000u64 + 1046);
+}
+
+#[test]
+fn dutch_auction_flow() {
+    let mut world = setup();
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .payment(TestEsdtTransfer(NFT, 1, 1))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.create_dutch_auction(
+                1,
+                BigUint::from(2000u64),
+                BigUint::from(1000u64),
+                1000,
+                OptionalValue::None,
+            );
+        });
+    world.current_block().block_timestamp_seconds(1500);
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .egld(1400)
+        .returns(ExpectError(4, "Insufficient payment"))
+        .whitebox(contract::contract_obj, |sc| sc.buy_dutch_auction(0));
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .egld(2000)
+        .whitebox(contract::contract_obj, |sc| sc.buy_dutch_auction(0));
+    world.check_account(BUYER).balance(1_000_000u64 - 1500);
+    world
+        .check_account(SELLER)
+        .balance(1_000_000u64 + 1500 - 74);
+}
+
+#[test]
+fn outbid_contracts_claim_their_refund() {
+    let mut world = setup();
+    let bidder_contract = TestSCAddress::new("bidder");
+    world
+        .account(bidder_contract)
+        .nonce(1)
+        .balance(1_000_000u64)
+        .code(CODE);
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .payment(TestEsdtTransfer(NFT, 1, 1))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.create_auction(
+                1,
+                BigUint::from(1000u64),
+                BigUint::from(100u64),
+                5000,
+                OptionalValue::None,
+            );
+        });
+    world
+        .tx()
+        .from(bidder_contract)
+        .to(SC)
+        .egld(1000)
+        .whitebox(contract::contract_obj, |sc| sc.place_bid(0));
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .egld(1100)
+        .whitebox(contract::contract_obj, |sc| sc.place_bid(0));
+    world
+        .check_account(bidder_contract)
+        .balance(1_000_000u64 - 1000);
+    world.query().to(SC).whitebox(contract::contract_obj, |sc| {
+        let pending: Vec<_> = sc
+            .get_pending_proceeds(bidder_contract.to_managed_address())
+            .into_iter()
+            .map(|entry| entry.into_tuple().1.to_u64().unwrap())
+            .collect();
+        assert_eq!(pending, vec![1000]);
+    });
+}
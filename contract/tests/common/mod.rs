@@ -0,0 +1,103 @@
+#![allow(dead_code)]
+
+use contract::{ContractGenerator, TemplateAttributes};
+use multiversx_sc_scenario::imports::*;
+
+pub const OWNER: TestAddress = TestAddress::new("owner");
+pub const SELLER: TestAddress = TestAddress::new("seller");
+pub const BUYER: TestAddress = TestAddress::new("buyer");
+pub const CREATOR: TestAddress = TestAddress::new("creator");
+pub const SC: TestSCAddress = TestSCAddress::new("gen");
+pub const CODE: MxscPath = MxscPath::new("output/contract.mxsc.json");
+pub const NFT: TestTokenIdentifier = TestTokenIdentifier::new("TMPL-123456");
+pub const USDC: TestTokenIdentifier = TestTokenIdentifier::new("USDC-123456");
+
+/// Deploys the contract with template 1 minted by CREATOR and sold on to SELLER
+pub fn setup() -> ScenarioWorld {
+    let mut world = ScenarioWorld::new();
+    world.register_contract(CODE, contract::ContractBuilder);
+    world.account(OWNER).nonce(1).balance(1_000_000u64);
+    world.account(SELLER).nonce(1).balance(1_000_000u64);
+    world
+        .account(CREATOR)
+        .nonce(1)
+        .balance(1_000_000_000_000_000_000u64);
+    world
+        .account(BUYER)
+        .nonce(1)
+        .balance(1_000_000u64)
+        .esdt_balance(USDC, 1_000_000u64);
+    world.current_block().block_timestamp_seconds(1000);
+    world
+        .tx()
+        .from(OWNER)
+        .raw_deploy()
+        .code(CODE)
+        .new_address(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.init(3, BigUint::from(50_000_000_000_000_000u64), 250);
+            sc.set_template_nft_token_id(NFT.to_token_identifier());
+        });
+    world.set_esdt_local_roles(SC, NFT.as_bytes(), &[EsdtLocalRole::NftCreate]);
+    mint(&mut world);
+    world
+        .tx()
+        .from(CREATOR)
+        .to(SELLER)
+        .payment(TestEsdtTransfer(NFT, 1, 1))
+        .raw_call("")
+        .run();
+    world
+}
+
+/// Completes a DeFi generation for CREATOR and mints it, returning the nonce
+pub fn mint(world: &mut ScenarioWorld) -> u64 {
+    let mut gen_id = 0;
+    world
+        .tx()
+        .from(CREATOR)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            gen_id =
+                sc.generate_contract(ManagedBuffer::from("token"), ManagedBuffer::from("DeFi"));
+        });
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.complete_generation(
+                gen_id,
+                ManagedBuffer::from("Qm"),
+                true,
+                false,
+                OptionalValue::Some(ManagedBuffer::from("v1")),
+            );
+        });
+    let mut nonce = 0;
+    world
+        .tx()
+        .from(CREATOR)
+        .to(SC)
+        .egld(50_000_000_000_000_000u64)
+        .whitebox(contract::contract_obj, |sc| {
+            nonce = sc.mint_template_nft(gen_id, ManagedBuffer::from("tmpl"));
+        });
+    nonce
+}
+
+/// Checks the untouched attributes of a template minted by `mint`
+pub fn assert_holds_nft(world: &mut ScenarioWorld, who: TestAddress, nonce: u64) {
+    let attrs = TemplateAttributes::<StaticApi> {
+        generation_id: nonce - 1,
+        category: ManagedBuffer::from("DeFi"),
+        code_hash: ManagedBuffer::from("Qm"),
+        creation_date: 1000,
+        uses: 0,
+        total_rating: 0,
+        rating_count: 0,
+    };
+    world
+        .check_account(who)
+        .esdt_nft_balance_and_attributes(NFT, nonce, 1, attrs);
+}
@@ -0,0 +1,227 @@
+mod common;
+
+use common::*;
+use contract::*;
+use multiversx_sc_scenario::imports::*;
+
+#[test]
+fn generation_deposit_flow() {
+    let mut world = setup();
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.timelock_delay().set(0);
+            let id = sc.propose_parameter_change(ParameterChange::GenerationDeposit(
+                BigUint::from(1000u64),
+            ));
+            sc.execute_parameter_change(id);
+        });
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .returns(ExpectError(4, "Generation deposit above maximum"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.propose_parameter_change(ParameterChange::GenerationDeposit(BigUint::from(
+                2_000_000_000_000_000_000u64,
+            )));
+        });
+    world
+        .tx()
+        .from(CREATOR)
+        .to(SC)
+        .returns(ExpectError(4, "Wrong generation deposit"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.generate_contract(ManagedBuffer::from("x"), ManagedBuffer::from("DeFi"));
+        });
+    let mut ids = (0, 0);
+    for slot in [0, 1] {
+        world
+            .tx()
+            .from(CREATOR)
+            .to(SC)
+            .egld(1000)
+            .whitebox(contract::contract_obj, |sc| {
+                let id =
+                    sc.generate_contract(ManagedBuffer::from("x"), ManagedBuffer::from("DeFi"));
+                assert_eq!(sc.get_generation_deposit(id), BigUint::from(1000u64));
+                if slot == 0 {
+                    ids.0 = id;
+                } else {
+                    ids.1 = id;
+                }
+            });
+    }
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.complete_generation(
+                ids.0,
+                ManagedBuffer::from("Qm"),
+                false,
+                false,
+                OptionalValue::None,
+            );
+            assert_eq!(sc.get_generation_deposit(ids.0), BigUint::zero());
+            sc.complete_generation(
+                ids.1,
+                ManagedBuffer::from("Qm"),
+                false,
+                true,
+                OptionalValue::None,
+            );
+            assert_eq!(sc.get_generation_deposit(ids.1), BigUint::from(1000u64));
+        });
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .returns(ExpectError(4, "Generation already completed"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.complete_generation(
+                ids.1,
+                ManagedBuffer::from("Qm"),
+                true,
+                false,
+                OptionalValue::None,
+            )
+        });
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| sc.pause(Subsystem::Generation));
+    world
+        .tx()
+        .from(CREATOR)
+        .to(SC)
+        .returns(ExpectError(4, "Generation already completed"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.reclaim_generation_deposit(ids.1)
+        });
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.slash_generation_deposit(ids.1);
+            assert_eq!(
+                sc.get_accrued_fees(FeeSource::Slashed, EgldOrEsdtTokenIdentifier::egld()),
+                BigUint::from(1000u64)
+            );
+        });
+    world
+        .check_account(SC)
+        .balance(1000u64 + 50_000_000_000_000_000u64);
+}
+
+fn stake_tier<M: ManagedTypeApi>(min_stake: u64, daily_limit: u64) -> StakeTier<M> {
+    StakeTier {
+        min_stake: BigUint::from(min_stake),
+        daily_limit,
+    }
+}
+
+#[test]
+fn staking_flow() {
+    let mut world = setup();
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .returns(ExpectError(4, "Tiers must increase in stake and limit"))
+        .whitebox(contract::contract_obj, |sc| {
+            let mut tiers = ManagedVec::new();
+            tiers.push(stake_tier(5000, 5));
+            tiers.push(stake_tier(1000, 10));
+            sc.propose_parameter_change(ParameterChange::StakeTiers(tiers));
+        });
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .returns(ExpectError(4, "Unbonding period above maximum"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.propose_parameter_change(ParameterChange::UnbondingPeriod(u64::MAX));
+        });
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            let mut tiers = ManagedVec::new();
+            tiers.push(stake_tier(1000, 5));
+            tiers.push(stake_tier(5000, 10));
+            sc.propose_parameter_change(ParameterChange::StakeTiers(tiers));
+        });
+    world
+        .current_block()
+        .block_timestamp_seconds(1000 + 2 * 86400);
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.execute_parameter_change(0);
+            assert_eq!(sc.get_stake_tiers().len(), 2);
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .egld(1000)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.stake();
+            let info = sc.get_stake_info(BUYER.to_managed_address());
+            assert_eq!((info.tier, info.daily_limit), (1, 5));
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            for _ in 0..5 {
+                sc.generate_contract(ManagedBuffer::from("x"), ManagedBuffer::from("DeFi"));
+            }
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .returns(ExpectError(4, "Daily generation limit reached"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.generate_contract(ManagedBuffer::from("x"), ManagedBuffer::from("DeFi"));
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.unstake(BigUint::from(400u64));
+            let info = sc.get_stake_info(BUYER.to_managed_address());
+            assert_eq!((info.tier, info.daily_limit), (0, 3));
+            assert_eq!(info.unbonding_amount, BigUint::from(400u64));
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .returns(ExpectError(4, "Unbonding period not over"))
+        .whitebox(contract::contract_obj, |sc| sc.withdraw_unbonded());
+    world
+        .current_block()
+        .block_timestamp_seconds(1000 + 9 * 86400);
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.withdraw_unbonded();
+            assert_eq!(sc.get_total_staked(), BigUint::from(600u64));
+        });
+    world.check_account(BUYER).balance(1_000_000u64 - 600);
+}
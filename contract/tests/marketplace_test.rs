@@ -0,0 +1,475 @@
+mod common;
+
+use common::*;
+use contract::*;
+use multiversx_sc_scenario::imports::*;
+
+/// SELLER lists template 1 for 10,000 USDC and BUYER pays 12,000 for it
+fn sell_for_usdc(world: &mut ScenarioWorld) {
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.add_accepted_payment_token(USDC.to_token_identifier());
+        });
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .payment(TestEsdtTransfer(NFT, 1, 1))
+        .whitebox(contract::contract_obj, |sc| {
+            let id = sc.list_template(
+                1,
+                BigUint::from(10_000u64),
+                OptionalValue::Some(EgldOrEsdtTokenIdentifier::esdt(USDC.to_token_identifier())),
+                OptionalValue::None,
+            );
+            assert_eq!(id, 0);
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .payment(TestEsdtTransfer(USDC, 0, 12_000))
+        .whitebox(contract::contract_obj, |sc| sc.purchase_template(0));
+}
+
+#[test]
+fn esdt_listing_flow() {
+    let mut world = setup();
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .payment(TestEsdtTransfer(NFT, 1, 1))
+        .returns(ExpectError(4, "Payment token not accepted"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.list_template(
+                1,
+                BigUint::from(10_000u64),
+                OptionalValue::Some(EgldOrEsdtTokenIdentifier::esdt(USDC.to_token_identifier())),
+                OptionalValue::None,
+            );
+        });
+    sell_for_usdc(&mut world);
+
+    // Overpayment is returned, the 2.5% fee and the creator royalty are kept back
+    world
+        .check_account(BUYER)
+        .esdt_balance(USDC, 1_000_000u64 - 10_000);
+    world.check_account(SELLER).esdt_balance(USDC, 9_500u64);
+    assert_holds_nft(&mut world, BUYER, 1);
+    world.query().to(SC).whitebox(contract::contract_obj, |sc| {
+        assert_eq!(
+            sc.get_accrued_fees(
+                FeeSource::Marketplace,
+                EgldOrEsdtTokenIdentifier::esdt(USDC.to_token_identifier())
+            ),
+            BigUint::from(250u64)
+        );
+    });
+}
+
+#[test]
+fn offer_flow() {
+    let mut world = setup();
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .egld(500)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.make_offer(1, 2000);
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .egld(700)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.make_offer(1, 2000);
+        });
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .returns(ExpectError(
+            4,
+            "Only the buyer can withdraw an active offer",
+        ))
+        .whitebox(contract::contract_obj, |sc| sc.withdraw_offer(0));
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .payment(TestEsdtTransfer(NFT, 1, 1))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.accept_offer(1);
+            assert_eq!(sc.get_offers_for_template(1).len(), 1);
+        });
+    world.check_account(SELLER).balance(1_000_000u64 + 700 - 34);
+    world.current_block().block_timestamp_seconds(2000);
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.withdraw_offer(0);
+            assert_eq!(sc.get_offers_by_buyer(BUYER.to_managed_address()).len(), 0);
+        });
+    world.check_account(BUYER).balance(1_000_000u64 - 700);
+}
+
+#[test]
+fn resales_pay_creator_royalties() {
+    let mut world = setup();
+    sell_for_usdc(&mut world);
+    world.check_account(CREATOR).esdt_balance(USDC, 250u64);
+
+    // The creator selling their own template gets no royalty on top
+    let nonce = mint(&mut world);
+    world
+        .tx()
+        .from(CREATOR)
+        .to(SC)
+        .payment(TestEsdtTransfer(NFT, nonce, 1))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.list_template(
+                nonce,
+                BigUint::from(10_000u64),
+                OptionalValue::Some(EgldOrEsdtTokenIdentifier::esdt(USDC.to_token_identifier())),
+                OptionalValue::None,
+            );
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .payment(TestEsdtTransfer(USDC, 0, 10_000))
+        .whitebox(contract::contract_obj, |sc| sc.purchase_template(1));
+    world
+        .check_account(CREATOR)
+        .esdt_balance(USDC, 250u64 + 9_750);
+    world.query().to(SC).whitebox(contract::contract_obj, |sc| {
+        assert_eq!(
+            sc.get_creator_royalties_earned(
+                CREATOR.to_managed_address(),
+                EgldOrEsdtTokenIdentifier::esdt(USDC.to_token_identifier())
+            ),
+            BigUint::from(250u64)
+        );
+    });
+}
+
+#[test]
+fn fees_are_split_between_recipients() {
+    let mut world = setup();
+    sell_for_usdc(&mut world);
+    let usdc = || EgldOrEsdtTokenIdentifier::esdt(USDC.to_token_identifier());
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .returns(ExpectError(4, "Shares must add up to 100%"))
+        .whitebox(contract::contract_obj, |sc| {
+            let mut recipients = MultiValueEncoded::new();
+            recipients.push(
+                (
+                    OWNER.to_managed_address(),
+                    4000u64,
+                    ManagedBuffer::from("dev"),
+                )
+                    .into(),
+            );
+            sc.set_fee_recipients(recipients);
+        });
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            let mut recipients = MultiValueEncoded::new();
+            recipients.push(
+                (
+                    SELLER.to_managed_address(),
+                    6000u64,
+                    ManagedBuffer::from("treasury"),
+                )
+                    .into(),
+            );
+            recipients.push(
+                (
+                    OWNER.to_managed_address(),
+                    4000u64,
+                    ManagedBuffer::from("dev"),
+                )
+                    .into(),
+            );
+            sc.set_fee_recipients(recipients);
+            sc.withdraw_fees(
+                FeeSource::Marketplace,
+                usdc(),
+                OptionalValue::Some(BigUint::from(100u64)),
+            );
+            sc.withdraw_fees(FeeSource::Marketplace, usdc(), OptionalValue::None);
+            assert_eq!(
+                sc.get_accrued_fees(FeeSource::Marketplace, usdc()),
+                BigUint::zero()
+            );
+        });
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .returns(ExpectError(4, "Nothing to withdraw"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.withdraw_fees(FeeSource::Marketplace, usdc(), OptionalValue::None);
+        });
+    world.check_account(OWNER).esdt_balance(USDC, 100u64);
+    world
+        .check_account(SELLER)
+        .esdt_balance(USDC, 9_500u64 + 150);
+}
+
+#[test]
+fn pull_payment_flow() {
+    let mut world = setup();
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.set_pull_payments_enabled(true)
+        });
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .payment(TestEsdtTransfer(NFT, 1, 1))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.list_template(
+                1,
+                BigUint::from(1000u64),
+                OptionalValue::None,
+                OptionalValue::None,
+            );
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .egld(1000)
+        .whitebox(contract::contract_obj, |sc| sc.purchase_template(0));
+    world.check_account(SELLER).balance(1_000_000u64);
+    world.query().to(SC).whitebox(contract::contract_obj, |sc| {
+        let p: Vec<_> = sc
+            .get_pending_proceeds(SELLER.to_managed_address())
+            .into_iter()
+            .map(|v| v.into_tuple().1.to_u64().unwrap())
+            .collect();
+        assert_eq!(p, vec![950]);
+    });
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.claim_proceeds(OptionalValue::None)
+        });
+    world
+        .tx()
+        .from(CREATOR)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.claim_proceeds(OptionalValue::Some(EgldOrEsdtTokenIdentifier::egld()))
+        });
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .returns(ExpectError(4, "Nothing to claim"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.claim_proceeds(OptionalValue::None)
+        });
+    world.check_account(SELLER).balance(1_000_950u64);
+}
+
+#[test]
+fn listing_expiry_flow() {
+    let mut world = setup();
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .payment(TestEsdtTransfer(NFT, 1, 1))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.list_template(
+                1,
+                BigUint::from(1000u64),
+                OptionalValue::None,
+                OptionalValue::Some(2000),
+            );
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .returns(ExpectError(4, "Listing has not expired"))
+        .whitebox(contract::contract_obj, |sc| sc.reclaim_expired_listing(0));
+    world.current_block().block_timestamp_seconds(2000);
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .egld(1000)
+        .returns(ExpectError(4, "Listing has expired"))
+        .whitebox(contract::contract_obj, |sc| sc.purchase_template(0));
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.reclaim_expired_listing(0);
+            assert!(sc.listings(0).is_empty());
+        });
+    assert_holds_nft(&mut world, SELLER, 1);
+}
+
+#[test]
+fn browsing_views() {
+    let mut world = setup();
+    let n2 = mint(&mut world);
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .payment(TestEsdtTransfer(NFT, 1, 1))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.list_template(
+                1,
+                BigUint::from(1000u64),
+                OptionalValue::None,
+                OptionalValue::None,
+            );
+        });
+    world
+        .tx()
+        .from(CREATOR)
+        .to(SC)
+        .payment(TestEsdtTransfer(NFT, n2, 1))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.list_template(
+                n2,
+                BigUint::from(5000u64),
+                OptionalValue::None,
+                OptionalValue::None,
+            );
+        });
+    world.query().to(SC).whitebox(contract::contract_obj, |sc| {
+        let all: Vec<_> = sc
+            .get_active_listings(0, 10)
+            .into_iter()
+            .map(|v| v.listing.id)
+            .collect();
+        assert_eq!(all, vec![0, 1]);
+        let page: Vec<_> = sc
+            .get_active_listings(1, 10)
+            .into_iter()
+            .map(|v| v.listing.id)
+            .collect();
+        assert_eq!(page, vec![1]);
+        assert_eq!(
+            sc.get_listings_by_seller(CREATOR.to_managed_address(), 0, 10)
+                .len(),
+            1
+        );
+        assert_eq!(
+            sc.get_listings_by_category(ManagedBuffer::from("DeFi"), 0, 10)
+                .len(),
+            2
+        );
+        let cheap: Vec<_> = sc
+            .get_listings_by_price_range(
+                EgldOrEsdtTokenIdentifier::egld(),
+                BigUint::from(0u64),
+                BigUint::from(2000u64),
+                0,
+                10,
+            )
+            .into_iter()
+            .map(|v| v.listing.id)
+            .collect();
+        assert_eq!(cheap, vec![0]);
+    });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .egld(1000)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.purchase_template(0);
+            assert_eq!(
+                sc.get_listings_by_category(ManagedBuffer::from("DeFi"), 0, 10)
+                    .len(),
+                1
+            );
+            assert_eq!(sc.get_active_listings(0, 10).len(), 1);
+        });
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.cancel_listing(1);
+            assert_eq!(
+                sc.get_listings_by_category(ManagedBuffer::from("DeFi"), 0, 10)
+                    .len(),
+                0
+            );
+            assert_eq!(
+                sc.get_listings_by_seller(CREATOR.to_managed_address(), 0, 10)
+                    .len(),
+                0
+            );
+        });
+}
+
+#[test]
+fn bundle_flow() {
+    let mut world = setup();
+    let n2 = mint(&mut world);
+    let n3 = mint(&mut world);
+    let mut pay = MultiEsdtPayment::<StaticApi>::new();
+    pay.push(EsdtTokenPayment::new(
+        NFT.to_token_identifier(),
+        n2,
+        BigUint::from(1u64),
+    ));
+    pay.push(EsdtTokenPayment::new(
+        NFT.to_token_identifier(),
+        n3,
+        BigUint::from(1u64),
+    ));
+    world
+        .tx()
+        .from(CREATOR)
+        .to(SC)
+        .payment(pay)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.list_bundle(BigUint::from(1001u64), OptionalValue::None);
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .egld(1001)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.purchase_bundle(0);
+            assert_eq!(sc.get_template_uses(n2), 1);
+            assert_eq!(sc.get_template_uses(n3), 1);
+            assert_eq!(sc.get_active_bundles(0, 10).len(), 0);
+        });
+    assert_holds_nft(&mut world, BUYER, n2);
+    assert_holds_nft(&mut world, BUYER, n3);
+}
@@ -0,0 +1,456 @@
+mod common;
+
+use common::*;
+use contract::*;
+use multiversx_sc_scenario::imports::*;
+
+#[test]
+fn verified_rating_flow() {
+    let mut world = setup();
+    world
+        .tx()
+        .from(CREATOR)
+        .to(SC)
+        .returns(ExpectError(4, "Cannot rate your own template"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.rate_template(1, 5, OptionalValue::None)
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .returns(ExpectError(4, "Only owners or renters can rate"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.rate_template(1, 5, OptionalValue::None)
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .returns(ExpectError(4, "Template not found"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.rate_template(7, 5, OptionalValue::None)
+        });
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .payment(TestEsdtTransfer(NFT, 1, 1))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.rate_template(1, 4, OptionalValue::None)
+        });
+    assert_holds_nft(&mut world, SELLER, 1);
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .payment(TestEsdtTransfer(NFT, 1, 1))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.list_template(
+                1,
+                BigUint::from(1000u64),
+                OptionalValue::None,
+                OptionalValue::None,
+            );
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .egld(1000)
+        .whitebox(contract::contract_obj, |sc| sc.purchase_template(0));
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .egld(10)
+        .returns(ExpectError(4, "Wrong token"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.rate_template(1, 2, OptionalValue::None)
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.rate_template(1, 2, OptionalValue::None);
+            let r = sc.get_template_rating(1);
+            assert_eq!((r.total_rating, r.rating_count), (6, 2));
+        });
+}
+
+#[test]
+fn expired_renters_cannot_rate() {
+    let mut world = setup();
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .payment(TestEsdtTransfer(NFT, 1, 1))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.list_for_rent(1, BigUint::from(100u64), 3, OptionalValue::None);
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .egld(100)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.rent_template(1, 1);
+            assert!(sc.can_rate_template(BUYER.to_managed_address(), 1));
+        });
+    world.current_block().block_timestamp_seconds(1000 + 86400);
+    world.query().to(SC).whitebox(contract::contract_obj, |sc| {
+        assert!(!sc.can_rate_template(BUYER.to_managed_address(), 1))
+    });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .returns(ExpectError(4, "Only owners or renters can rate"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.rate_template(1, 5, OptionalValue::None)
+        });
+}
+
+#[test]
+fn editable_reviews_flow() {
+    let mut world = setup();
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .payment(TestEsdtTransfer(NFT, 1, 1))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.rate_template(1, 2, OptionalValue::Some(ManagedBuffer::from("QmReview")))
+        });
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.update_rating(1, 5, OptionalValue::None);
+            let r = sc.get_template_rating(1);
+            assert_eq!((r.total_rating, r.rating_count), (5, 1));
+            let reviews: Vec<_> = sc.get_template_reviews(1, 0, 10).into_iter().collect();
+            assert_eq!(reviews.len(), 1);
+            assert_eq!(reviews[0].rating, 5);
+            assert_eq!(reviews[0].review_cid, ManagedBuffer::from("QmReview"));
+        });
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.hide_review(1, SELLER.to_managed_address(), true)
+        });
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.remove_rating(1);
+            let r = sc.get_template_rating(1);
+            assert_eq!((r.total_rating, r.rating_count), (0, 0));
+            assert_eq!(sc.get_template_reviews(1, 0, 10).len(), 0);
+            assert!(sc.template_ratings(1).is_empty());
+        });
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .payment(TestEsdtTransfer(NFT, 1, 1))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.rate_template(1, 3, OptionalValue::Some(ManagedBuffer::from("QmAgain")));
+            assert_eq!(sc.get_template_reviews(1, 0, 10).len(), 1);
+        });
+}
+
+#[test]
+fn moderation_flow() {
+    let mut world = setup();
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.grant_role(Role::Moderator, BUYER.to_managed_address());
+            sc.set_report_stake(BigUint::from(100u64));
+        });
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .payment(TestEsdtTransfer(NFT, 1, 1))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.rate_template(1, 4, OptionalValue::None)
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.hide_review(1, SELLER.to_managed_address(), true);
+            assert_eq!(sc.get_template_reviews(1, 0, 10).len(), 0);
+            sc.flag_template(1, ManagedBuffer::from("QmWhy"));
+        });
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .payment(TestEsdtTransfer(NFT, 1, 1))
+        .returns(ExpectError(4, "Template flagged by moderators"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.list_template(
+                1,
+                BigUint::from(1000u64),
+                OptionalValue::None,
+                OptionalValue::None,
+            );
+        });
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .egld(100)
+        .whitebox(contract::contract_obj, |sc| {
+            let id = sc.report_template(1, ManagedBuffer::from("QmReport"));
+            assert_eq!(sc.get_open_reports(0, 10).len(), 1);
+            assert_eq!(id, 0);
+        });
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .returns(ExpectError(4, "Only owner or moderator"))
+        .whitebox(contract::contract_obj, |sc| sc.resolve_report(0, false));
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.resolve_report(0, false);
+            assert_eq!(
+                sc.get_accrued_fees(FeeSource::Slashed, EgldOrEsdtTokenIdentifier::egld()),
+                BigUint::from(100u64)
+            );
+            assert_eq!(sc.get_open_reports(0, 10).len(), 0);
+            sc.unflag_template(1);
+            assert!(!sc.get_is_template_flagged(1));
+        });
+}
+
+#[test]
+fn leaderboard_flow() {
+    let mut world = setup();
+    let second = mint(&mut world);
+    world
+        .tx()
+        .from(CREATOR)
+        .to(BUYER)
+        .payment(TestEsdtTransfer(NFT, second, 1))
+        .raw_call("")
+        .run();
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .payment(TestEsdtTransfer(NFT, 1, 1))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.rate_template(1, 5, OptionalValue::None);
+            assert_eq!(sc.get_bayesian_score(1), 500);
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .payment(TestEsdtTransfer(NFT, second, 1))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.rate_template(second, 3, OptionalValue::None);
+            assert_eq!(sc.get_bayesian_score(second), 390);
+            assert_eq!(sc.get_bayesian_score(1), 409);
+            // Template 1 is rescored under the new platform-wide mean
+            let top: Vec<_> = sc
+                .get_top_templates(ManagedBuffer::from("DeFi"))
+                .into_iter()
+                .map(|e| (e.nft_nonce, e.score))
+                .collect();
+            assert_eq!(top, vec![(1, 409), (second, 390)]);
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.update_rating(second, 5, OptionalValue::None);
+            let top: Vec<_> = sc
+                .get_top_templates(ManagedBuffer::from("DeFi"))
+                .into_iter()
+                .map(|e| (e.nft_nonce, e.score))
+                .collect();
+            assert_eq!(top, vec![(1, 500), (second, 500)]);
+            sc.remove_rating(second);
+            assert_eq!(sc.get_top_templates(ManagedBuffer::from("DeFi")).len(), 1);
+        });
+}
+
+#[test]
+fn generation_feedback_flow() {
+    let mut world = setup();
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .returns(ExpectError(4, "Not the generation creator"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.rate_generation(0, 4, true, false)
+        });
+    world
+        .tx()
+        .from(CREATOR)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.rate_generation(0, 4, true, false);
+            let stats = sc.get_category_generation_stats(ManagedBuffer::from("DeFi"));
+            assert_eq!(
+                (
+                    stats.rating_count,
+                    stats.total_quality,
+                    stats.compiled_as_is_count
+                ),
+                (1, 4, 1)
+            );
+            let stats = sc.get_generator_version_stats(ManagedBuffer::from("v1"));
+            assert_eq!(stats.rating_count, 1);
+            assert_eq!(sc.get_generator_versions().len(), 1);
+            assert_eq!(
+                sc.get_category_generation_stats(ManagedBuffer::from("NFT"))
+                    .rating_count,
+                0
+            );
+        });
+    world
+        .tx()
+        .from(CREATOR)
+        .to(SC)
+        .returns(ExpectError(4, "Generation already rated"))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.rate_generation(0, 2, false, true)
+        });
+}
+
+#[test]
+fn creator_profile_flow() {
+    let mut world = setup();
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .payment(TestEsdtTransfer(NFT, 1, 1))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.list_template(
+                1,
+                BigUint::from(1000u64),
+                OptionalValue::None,
+                OptionalValue::None,
+            );
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .egld(1000)
+        .whitebox(contract::contract_obj, |sc| sc.purchase_template(0));
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.rate_template(1, 4, OptionalValue::None);
+            let profile = sc.get_creator_profile(CREATOR.to_managed_address());
+            assert_eq!(profile.generation_count, 1);
+            assert_eq!(profile.minted_templates, 1);
+            assert_eq!(profile.sales_count, 1);
+            assert_eq!(profile.sales_volume.len(), 1);
+            assert_eq!(profile.sales_volume.get(0).amount, BigUint::from(1000u64));
+            assert_eq!(profile.average_rating, 400);
+            assert_eq!(profile.achievements.len(), 1);
+            // 400 rating + 10 mint + 25 sale + 50 achievement
+            assert_eq!(profile.reputation, 485);
+            let top: Vec<_> = sc.get_top_creators().into_iter().collect();
+            assert_eq!(top.len(), 2);
+            assert_eq!(top[0].creator, CREATOR.to_managed_address());
+            assert_eq!(top[0].reputation, 485);
+        });
+}
+
+#[test]
+fn top_creators_are_rescored() {
+    let mut world = setup();
+    let rival = TestAddress::new("rival");
+    world
+        .account(rival)
+        .nonce(1)
+        .balance(1_000_000_000_000_000_000u64);
+    world
+        .tx()
+        .from(rival)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.generate_contract(ManagedBuffer::from("vault"), ManagedBuffer::from("DeFi"));
+        });
+    world
+        .tx()
+        .from(OWNER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.complete_generation(
+                1,
+                ManagedBuffer::from("Qm"),
+                true,
+                false,
+                OptionalValue::Some(ManagedBuffer::from("v1")),
+            );
+        });
+    world
+        .tx()
+        .from(rival)
+        .to(SC)
+        .egld(50_000_000_000_000_000u64)
+        .whitebox(contract::contract_obj, |sc| {
+            assert_eq!(sc.mint_template_nft(1, ManagedBuffer::from("tmpl")), 2);
+        });
+    world
+        .tx()
+        .from(rival)
+        .to(SELLER)
+        .payment(TestEsdtTransfer(NFT, 2, 1))
+        .raw_call("")
+        .run();
+
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .payment(TestEsdtTransfer(NFT, 1, 1))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.rate_template(1, 5, OptionalValue::None)
+        });
+    // Rating the rival lowers the platform-wide mean behind CREATOR's score
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .payment(TestEsdtTransfer(NFT, 2, 1))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.rate_template(2, 1, OptionalValue::None)
+        });
+    world.query().to(SC).whitebox(contract::contract_obj, |sc| {
+        let top: Vec<_> = sc.get_top_creators().into_iter().collect();
+        assert_eq!(top[0].creator, CREATOR.to_managed_address());
+        for entry in top {
+            let profile = sc.get_creator_profile(entry.creator.clone());
+            assert_eq!(entry.reputation, profile.reputation);
+        }
+    });
+}
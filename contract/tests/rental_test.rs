@@ -0,0 +1,48 @@
+mod common;
+
+use common::*;
+use contract::*;
+use multiversx_sc_scenario::imports::*;
+
+#[test]
+fn rental_flow() {
+    let mut world = setup();
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .payment(TestEsdtTransfer(NFT, 1, 1))
+        .whitebox(contract::contract_obj, |sc| {
+            sc.list_for_rent(1, BigUint::from(100u64), 3, OptionalValue::None);
+        });
+    world
+        .tx()
+        .from(BUYER)
+        .to(SC)
+        .egld(200)
+        .whitebox(contract::contract_obj, |sc| {
+            sc.rent_template(1, 2);
+            assert!(sc.has_access(BUYER.to_managed_address(), 1));
+            assert!(!sc.has_access(OWNER.to_managed_address(), 1));
+            assert!(sc.has_access(SELLER.to_managed_address(), 1));
+        });
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .returns(ExpectError(4, "Template still has active rentals"))
+        .whitebox(contract::contract_obj, |sc| sc.withdraw_rented_template(1));
+    world
+        .current_block()
+        .block_timestamp_seconds(1000 + 2 * 86400);
+    world.query().to(SC).whitebox(contract::contract_obj, |sc| {
+        assert!(!sc.has_access(BUYER.to_managed_address(), 1))
+    });
+    world
+        .tx()
+        .from(SELLER)
+        .to(SC)
+        .whitebox(contract::contract_obj, |sc| sc.withdraw_rented_template(1));
+    assert_holds_nft(&mut world, SELLER, 1);
+    world.check_account(SELLER).balance(1_000_000u64 + 190);
+}
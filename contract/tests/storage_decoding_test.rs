@@ -0,0 +1,173 @@
+use contract::*;
+use multiversx_sc::codec::{
+    self, DecodeErrorHandler, NestedDecode, NestedEncode, TopDecode, TopDecodeInput,
+    top_encode_to_vec_u8_or_panic,
+};
+use multiversx_sc_scenario::imports::*;
+
+fn generation() -> Generation<StaticApi> {
+    Generation {
+        id: 7,
+        creator: ManagedAddress::from(&[1u8; 32]),
+        description: ManagedBuffer::from("token"),
+        category: ManagedBuffer::from("DeFi"),
+        timestamp: 1000,
+        status: GenerationStatus::Completed,
+        code_hash: ManagedBuffer::from("Qm"),
+        nft_nonce: 3,
+    }
+}
+
+fn listing() -> Listing<StaticApi> {
+    Listing {
+        id: 2,
+        seller: ManagedAddress::from(&[2u8; 32]),
+        nft_nonce: 3,
+        payment_token: EgldOrEsdtTokenIdentifier::egld(),
+        price: BigUint::from(1000u64),
+        expires_at: 5000,
+    }
+}
+
+fn attributes() -> TemplateAttributes<StaticApi> {
+    TemplateAttributes {
+        generation_id: 7,
+        category: ManagedBuffer::from("DeFi"),
+        code_hash: ManagedBuffer::from("Qm"),
+        creation_date: 1000,
+        uses: 2,
+        total_rating: 9,
+        rating_count: 2,
+    }
+}
+
+/// `Generation` as it would look with a field appended, decoded the way the
+/// contract decodes it
+struct GrownGeneration {
+    generation: Generation<StaticApi>,
+    generator_version: ManagedBuffer<StaticApi>,
+}
+
+impl TopDecode for GrownGeneration {
+    fn top_decode_or_handle_err<I, H>(input: I, h: H) -> Result<Self, H::HandledErr>
+    where
+        I: TopDecodeInput,
+        H: DecodeErrorHandler,
+    {
+        let mut input = input.into_nested_buffer();
+        let generation = Generation {
+            id: u64::dep_decode_or_handle_err(&mut input, h)?,
+            creator: ManagedAddress::dep_decode_or_handle_err(&mut input, h)?,
+            description: ManagedBuffer::dep_decode_or_handle_err(&mut input, h)?,
+            category: ManagedBuffer::dep_decode_or_handle_err(&mut input, h)?,
+            timestamp: u64::dep_decode_or_handle_err(&mut input, h)?,
+            status: GenerationStatus::dep_decode_or_handle_err(&mut input, h)?,
+            code_hash: ManagedBuffer::dep_decode_or_handle_err(&mut input, h)?,
+            nft_nonce: u64::dep_decode_or_handle_err(&mut input, h)?,
+        };
+        let generator_version = dep_decode_appended(&mut input, h)?;
+        top_decode_end(&input, h)?;
+        Ok(GrownGeneration {
+            generation,
+            generator_version,
+        })
+    }
+}
+
+/// `TemplateAttributes` as it would look with a field appended, decoded the
+/// way the contract decodes it
+struct GrownTemplateAttributes {
+    attributes: TemplateAttributes<StaticApi>,
+    license_id: u64,
+}
+
+impl TopDecode for GrownTemplateAttributes {
+    fn top_decode_or_handle_err<I, H>(input: I, h: H) -> Result<Self, H::HandledErr>
+    where
+        I: TopDecodeInput,
+        H: DecodeErrorHandler,
+    {
+        let mut input = input.into_nested_buffer();
+        let attributes = TemplateAttributes {
+            generation_id: u64::dep_decode_or_handle_err(&mut input, h)?,
+            category: ManagedBuffer::dep_decode_or_handle_err(&mut input, h)?,
+            code_hash: ManagedBuffer::dep_decode_or_handle_err(&mut input, h)?,
+            creation_date: u64::dep_decode_or_handle_err(&mut input, h)?,
+            uses: u64::dep_decode_or_handle_err(&mut input, h)?,
+            total_rating: u64::dep_decode_or_handle_err(&mut input, h)?,
+            rating_count: u64::dep_decode_or_handle_err(&mut input, h)?,
+        };
+        let license_id = dep_decode_appended(&mut input, h)?;
+        top_decode_end(&input, h)?;
+        Ok(GrownTemplateAttributes {
+            attributes,
+            license_id,
+        })
+    }
+}
+
+fn decode<T: TopDecode>(bytes: &[u8]) -> Result<T, codec::DecodeError> {
+    T::top_decode(bytes)
+}
+
+fn append<T: NestedEncode>(bytes: &[u8], field: &T) -> Vec<u8> {
+    let mut grown = bytes.to_vec();
+    field.dep_encode(&mut grown).unwrap();
+    grown
+}
+
+#[test]
+fn stored_structs_round_trip() {
+    let bytes = top_encode_to_vec_u8_or_panic(&generation());
+    assert!(decode::<Generation<StaticApi>>(&bytes).unwrap() == generation());
+
+    let bytes = top_encode_to_vec_u8_or_panic(&listing());
+    let decoded = decode::<Listing<StaticApi>>(&bytes).unwrap();
+    assert_eq!(decoded.price, BigUint::from(1000u64));
+    assert_eq!(decoded.expires_at, 5000);
+
+    let bytes = top_encode_to_vec_u8_or_panic(&attributes());
+    let decoded = decode::<TemplateAttributes<StaticApi>>(&bytes).unwrap();
+    assert_eq!(decoded.generation_id, 7);
+    assert_eq!(decoded.code_hash, ManagedBuffer::from("Qm"));
+    assert_eq!((decoded.total_rating, decoded.rating_count), (9, 2));
+}
+
+#[test]
+fn stored_generation_decodes_after_a_field_is_appended() {
+    let bytes = top_encode_to_vec_u8_or_panic(&generation());
+
+    let grown = decode::<GrownGeneration>(&bytes).unwrap();
+    assert!(grown.generation == generation());
+    assert!(grown.generator_version.is_empty());
+
+    let grown_bytes = append(&bytes, &ManagedBuffer::<StaticApi>::from("v2"));
+    let grown = decode::<GrownGeneration>(&grown_bytes).unwrap();
+    assert_eq!(grown.generator_version, ManagedBuffer::from("v2"));
+}
+
+#[test]
+fn minted_attributes_decode_after_a_field_is_appended() {
+    let bytes = top_encode_to_vec_u8_or_panic(&attributes());
+
+    let grown = decode::<GrownTemplateAttributes>(&bytes).unwrap();
+    assert_eq!(grown.attributes.generation_id, 7);
+    assert_eq!(grown.attributes.rating_count, 2);
+    assert_eq!(grown.license_id, 0);
+
+    let grown = decode::<GrownTemplateAttributes>(&append(&bytes, &4u64)).unwrap();
+    assert_eq!(grown.license_id, 4);
+}
+
+#[test]
+fn unknown_trailing_bytes_and_missing_fields_are_rejected() {
+    let bytes = top_encode_to_vec_u8_or_panic(&listing());
+    assert!(decode::<Listing<StaticApi>>(&append(&bytes, &1u8)).is_err());
+
+    let bytes = top_encode_to_vec_u8_or_panic(&attributes());
+    assert!(decode::<TemplateAttributes<StaticApi>>(&append(&bytes, &1u8)).is_err());
+
+    // Only appended fields may be missing
+    let bytes = top_encode_to_vec_u8_or_panic(&generation());
+    assert!(decode::<Generation<StaticApi>>(&bytes[..bytes.len() - 8]).is_err());
+}
@@ -6,9 +6,9 @@
 
 // Init:                                 1
 // Upgrade:                              1
-// Endpoints:                           19
+// Endpoints:                           130
 // Async Callback (empty):               1
-// Total number of exported functions:  22
+// Total number of exported functions:  133
 
 #![no_std]
 
@@ -21,24 +21,135 @@ multiversx_sc_wasm_adapter::endpoints! {
         init => init
         upgrade => upgrade
         setTemplateNftTokenId => set_template_nft_token_id
+        migrateStorage => migrate_storage
         generateContract => generate_contract
         completeGeneration => complete_generation
+        reclaimGenerationDeposit => reclaim_generation_deposit
+        stake => stake
+        unstake => unstake
+        withdrawUnbonded => withdraw_unbonded
+        setStakeToken => set_stake_token
         mintTemplateNFT => mint_template_nft
         listTemplate => list_template
         purchaseTemplate => purchase_template
+        claimProceeds => claim_proceeds
         cancelListing => cancel_listing
+        withdrawListing => withdraw_listing
+        reclaimExpiredListing => reclaim_expired_listing
+        listBundle => list_bundle
+        purchaseBundle => purchase_bundle
+        cancelBundle => cancel_bundle
+        createAuction => create_auction
+        placeBid => place_bid
+        endAuction => end_auction
+        cancelAuction => cancel_auction
+        createDutchAuction => create_dutch_auction
+        buyDutchAuction => buy_dutch_auction
+        cancelDutchAuction => cancel_dutch_auction
+        makeOffer => make_offer
+        acceptOffer => accept_offer
+        withdrawOffer => withdraw_offer
+        listForRent => list_for_rent
+        rentTemplate => rent_template
+        setRentalAvailability => set_rental_availability
+        withdrawRentedTemplate => withdraw_rented_template
         rateTemplate => rate_template
-        setDailyLimit => set_daily_limit
-        setMintingFee => set_minting_fee
+        updateRating => update_rating
+        removeRating => remove_rating
+        rateGeneration => rate_generation
+        hideReview => hide_review
+        flagTemplate => flag_template
+        unflagTemplate => unflag_template
+        reportTemplate => report_template
+        resolveReport => resolve_report
+        slashGenerationDeposit => slash_generation_deposit
+        setReportStake => set_report_stake
+        blockAddresses => block_addresses
+        unblockAddresses => unblock_addresses
+        setAllowlistOnly => set_allowlist_only
+        addToAllowlist => add_to_allowlist
+        removeFromAllowlist => remove_from_allowlist
+        pause => pause
+        unpause => unpause
+        grantRole => grant_role
+        revokeRole => revoke_role
+        renounceRole => renounce_role
+        addAcceptedPaymentToken => add_accepted_payment_token
+        removeAcceptedPaymentToken => remove_accepted_payment_token
+        setPullPaymentsEnabled => set_pull_payments_enabled
+        proposeParameterChange => propose_parameter_change
+        executeParameterChange => execute_parameter_change
+        cancelParameterChange => cancel_parameter_change
+        setFeeRecipients => set_fee_recipients
         withdrawFees => withdraw_fees
         getGeneration => get_generation
         getListing => get_listing
+        getActiveListings => get_active_listings
+        getListingsBySeller => get_listings_by_seller
+        getListingsByCategory => get_listings_by_category
+        getListingsByPriceRange => get_listings_by_price_range
+        getBundle => get_bundle
+        getActiveBundles => get_active_bundles
+        getRentalOffer => get_rental_offer
+        hasAccess => has_access
+        getAccessExpiry => get_access_expiry
+        getAuction => get_auction
+        getActiveAuctions => get_active_auctions
+        getAuctionBids => get_auction_bids
+        getDutchAuction => get_dutch_auction
+        getDutchAuctionPrice => get_dutch_auction_price
+        getOffer => get_offer
+        getOffersForTemplate => get_offers_for_template
+        getOffersByBuyer => get_offers_by_buyer
         getUserGenerationsToday => get_user_generations_today
         getUserGenerationCount => get_user_generation_count
         getTemplateRating => get_template_rating
+        canRateTemplate => can_rate_template
+        getTemplateReviews => get_template_reviews
+        getBayesianScore => get_bayesian_score
+        getTopTemplates => get_top_templates
+        isReviewHidden => is_review_hidden
+        isTemplateFlagged => get_is_template_flagged
+        getFlaggedTemplates => get_flagged_templates
+        getReport => get_report
+        getOpenReports => get_open_reports
+        getReportStake => get_report_stake
+        getCreatorProfile => get_creator_profile
+        getTopCreators => get_top_creators
+        getGenerationFeedback => get_generation_feedback
+        getCategoryGenerationStats => get_category_generation_stats
+        getGeneratorVersionStats => get_generator_version_stats
+        getGeneratorVersion => get_generator_version
+        getGeneratorVersions => get_generator_versions
+        getUserReview => get_user_review
         getTemplateUses => get_template_uses
         getDailyLimit => get_daily_limit
         getMintingFee => get_minting_fee
+        getCreatorRoyaltiesEarned => get_creator_royalties_earned
+        getPendingProceeds => get_pending_proceeds
+        isPullPaymentsEnabled => is_pull_payments_enabled
+        isPaused => is_paused
+        getPendingParameterChanges => get_pending_parameter_changes
+        getStakeInfo => get_stake_info
+        getStakeTiers => get_stake_tiers
+        getStakeToken => get_stake_token_view
+        getUnbondingPeriod => get_unbonding_period
+        getTotalStaked => get_total_staked
+        getGenerationDepositAmount => get_generation_deposit_amount
+        getGenerationDeposit => get_generation_deposit
+        getStorageVersion => get_storage_version
+        getTimelockDelay => get_timelock_delay
+        isAddressAllowed => get_is_address_allowed
+        isAddressBlocked => is_address_blocked
+        getBlockedAddresses => get_blocked_addresses
+        isAllowlistOnly => is_allowlist_only
+        getAllowlist => get_allowlist
+        getRoleMembers => get_role_members
+        hasRole => get_has_role
+        getAcceptedPaymentTokens => get_accepted_payment_tokens
+        getAccruedFees => get_accrued_fees
+        getFeeRecipients => get_fee_recipients
+        getPlatformFee => get_platform_fee
     )
 }
 